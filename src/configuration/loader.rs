@@ -1,7 +1,13 @@
+use std::path::PathBuf;
+
 use ansi_term::{ANSIGenericString, Color};
 use confy::ConfyError;
 use serde::{Deserialize, Serialize};
 
+use crate::interpreting::stdlib::DEFAULT_MAX_RECURSION_DEPTH;
+
+pub const DEFAULT_MAX_HISTORY_SIZE: usize = 1000;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Greeting {
     pub greeting_message: String,
@@ -17,6 +23,10 @@ pub struct Prompt {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     pub general_color: String,
+    #[serde(default = "default_max_recursion_depth")]
+    pub max_recursion_depth: usize,
+    #[serde(default = "default_max_history_size")]
+    pub max_history_size: usize,
     pub greeting: Greeting,
     pub prompt: Prompt,
 }
@@ -53,12 +63,28 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             general_color: "purple".to_string(),
+            max_recursion_depth: DEFAULT_MAX_RECURSION_DEPTH,
+            max_history_size: DEFAULT_MAX_HISTORY_SIZE,
             greeting: Greeting::default(),
             prompt: Prompt::default(),
         }
     }
 }
 
+fn default_max_recursion_depth() -> usize {
+    DEFAULT_MAX_RECURSION_DEPTH
+}
+
+fn default_max_history_size() -> usize {
+    DEFAULT_MAX_HISTORY_SIZE
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    confy::get_configuration_file_path("mini-calc", Some("mini-calc"))
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.join("history")))
+}
+
 pub fn load() -> Result<Config, confy::ConfyError> {
     let cfg: Config = confy::load("mini-calc", Some("mini-calc"))?;
     Ok(cfg)
@@ -145,3 +171,49 @@ pub fn load_config<'a>(config: Config) -> Loaded<'a> {
         prompt_style: load_color(config.prompt.prompt_color),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::configuration::loader::{history_path, Config, DEFAULT_MAX_HISTORY_SIZE};
+    use crate::interpreting::stdlib::DEFAULT_MAX_RECURSION_DEPTH;
+
+    #[test]
+    fn test_history_path_is_next_to_config() {
+        let config = confy::get_configuration_file_path("mini-calc", Some("mini-calc")).unwrap();
+        let history = history_path().unwrap();
+        assert_eq!(history.parent(), config.parent());
+        assert_eq!(history.file_name().unwrap(), "history");
+    }
+
+    #[test]
+    fn test_config_defaults_for_missing_settings() {
+        let path = std::env::temp_dir().join("mini-calc-test-defaults.toml");
+        fs::write(
+            &path,
+            "general_color = \"purple\"\n\n[greeting]\ngreeting_message = \"hi\"\ngreeting_color = \"blue\"\n\n[prompt]\nprompt = \"> \"\nprompt_color = \"cyan\"\n",
+        )
+        .unwrap();
+        let config: Config = confy::load_path(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(config.max_recursion_depth, DEFAULT_MAX_RECURSION_DEPTH);
+        assert_eq!(config.max_history_size, DEFAULT_MAX_HISTORY_SIZE);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let path = std::env::temp_dir().join("mini-calc-test-round-trip.toml");
+        let config = Config {
+            max_recursion_depth: 42,
+            max_history_size: 7,
+            ..Config::default()
+        };
+        confy::store_path(&path, &config).unwrap();
+        let loaded: Config = confy::load_path(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.max_recursion_depth, 42);
+        assert_eq!(loaded.max_history_size, 7);
+        assert_eq!(loaded.general_color, config.general_color);
+    }
+}
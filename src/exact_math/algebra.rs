@@ -0,0 +1,990 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::exact_math::rationals::Rationals;
+use crate::interpreting::error::{error, ErrorKind};
+use crate::interpreting::function::{add, divide, mult};
+use crate::parsing::ast::{Ast, Parameters, Position};
+use crate::utils::complex_utils::{format_part, to_complex};
+
+const MAX_ROOT_CANDIDATE: i64 = 1_000_000;
+const MAX_ROOT_ITERATIONS: usize = 1000;
+const MAX_TERM_PRODUCTS: usize = 10_000;
+
+pub type Monomial = Vec<(String, u32)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    pub terms: BTreeMap<Monomial, Parameters>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factors {
+    pub constant: Parameters,
+    pub num: Vec<(Polynomial, u32)>,
+    pub den: Vec<(Polynomial, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub num: Polynomial,
+    pub den: Polynomial,
+    pub factors: Option<Factors>,
+}
+
+fn coefficient(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Rational(r) => Parameters::from_rational(r),
+        p => p,
+    }
+}
+
+fn c_add(a: &Parameters, b: &Parameters) -> Parameters {
+    coefficient(add(a.clone(), b.clone(), None))
+}
+
+fn c_mult(a: &Parameters, b: &Parameters) -> Parameters {
+    coefficient(mult(a.clone(), b.clone(), None))
+}
+
+fn c_divide(a: &Parameters, b: &Parameters) -> Parameters {
+    coefficient(divide(a.clone(), b.clone(), None))
+}
+
+fn is_zero(c: &Parameters) -> bool {
+    c.to_f64() == Some(0.0)
+}
+
+fn is_one(c: &Parameters) -> bool {
+    c.to_f64() == Some(1.0)
+}
+
+fn is_negative(c: &Parameters) -> bool {
+    matches!(c.to_f64(), Some(f) if f < 0.0)
+}
+
+fn is_exact(c: &Parameters) -> bool {
+    matches!(c, Parameters::Int(_) | Parameters::Rational(_))
+}
+
+pub fn is_coefficient(p: &Parameters) -> bool {
+    matches!(
+        p,
+        Parameters::Int(_) | Parameters::Rational(_) | Parameters::Float(_)
+    )
+}
+
+fn degree(m: &Monomial) -> u32 {
+    m.iter().map(|(_, e)| e).sum()
+}
+
+fn exponent(m: &Monomial, var: &str) -> u32 {
+    m.iter().find(|(v, _)| v == var).map_or(0, |(_, e)| *e)
+}
+
+fn power_of(var: &str, e: u32) -> Monomial {
+    if e == 0 {
+        vec![]
+    } else {
+        vec![(var.to_string(), e)]
+    }
+}
+
+fn monomial_mult(a: &Monomial, b: &Monomial) -> Option<Monomial> {
+    let mut m: BTreeMap<String, u32> = a.iter().cloned().collect();
+    for (v, e) in b {
+        let current = m.entry(v.clone()).or_insert(0);
+        *current = current.checked_add(*e)?;
+    }
+    Some(m.into_iter().collect())
+}
+
+fn too_large() -> Parameters {
+    error(
+        ErrorKind::Overflow,
+        "The expression is too large to be expanded".to_string(),
+    )
+}
+
+fn monomial_divide(a: &Monomial, b: &Monomial) -> Option<Monomial> {
+    let mut m: BTreeMap<String, u32> = a.iter().cloned().collect();
+    for (v, e) in b {
+        let current = m.get(v).copied().unwrap_or(0);
+        if current < *e {
+            return None;
+        }
+        m.insert(v.clone(), current - e);
+    }
+    Some(m.into_iter().filter(|(_, e)| *e > 0).collect())
+}
+
+fn graded_order(a: &Monomial, b: &Monomial) -> Ordering {
+    degree(a).cmp(&degree(b)).then_with(|| {
+        let vars: BTreeSet<&String> = a.iter().chain(b.iter()).map(|(v, _)| v).collect();
+        vars.into_iter()
+            .map(|v| exponent(a, v).cmp(&exponent(b, v)))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+fn monomial_to_string(m: &Monomial) -> String {
+    let vs: Vec<String> = m
+        .iter()
+        .map(|(v, e)| match e {
+            1 => v.to_string(),
+            e => format!("{v}^{e}"),
+        })
+        .collect();
+    vs.join("*")
+}
+
+fn coefficient_to_string(c: &Parameters) -> String {
+    match c {
+        Parameters::Float(f) => format_part(*f),
+        c => c.to_string(),
+    }
+}
+
+fn term_to_string(c: &Parameters, m: &Monomial) -> String {
+    if m.is_empty() {
+        return coefficient_to_string(c);
+    }
+    let vars = monomial_to_string(m);
+    match c {
+        Parameters::Rational(r) => {
+            let r = r.clone().reduce();
+            let over = match r.over.to_i64() {
+                Some(1) => "".to_string(),
+                _ => r.over.to_string(),
+            };
+            if r.under.is_one() {
+                format!("{over}{vars}")
+            } else {
+                format!("{over}{vars}/{}", r.under)
+            }
+        }
+        c if is_one(c) => vars,
+        c => format!("{}{vars}", coefficient_to_string(c)),
+    }
+}
+
+impl Polynomial {
+    pub fn zero() -> Self {
+        Polynomial {
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn constant(c: Parameters) -> Self {
+        let mut p = Polynomial::zero();
+        p.insert(vec![], c);
+        p
+    }
+
+    pub fn variable(name: &str) -> Self {
+        Polynomial::power(name, 1)
+    }
+
+    pub fn power(name: &str, e: u32) -> Self {
+        let mut p = Polynomial::zero();
+        p.insert(power_of(name, e), Parameters::Int(1));
+        p
+    }
+
+    fn insert(&mut self, m: Monomial, c: Parameters) {
+        let c = match self.terms.remove(&m) {
+            Some(old) => c_add(&old, &c),
+            None => coefficient(c),
+        };
+        if !is_zero(&c) {
+            self.terms.insert(m, c);
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn constant_value(&self) -> Option<Parameters> {
+        match self.terms.len() {
+            0 => Some(Parameters::Int(0)),
+            1 => self.terms.get(&vec![]).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn variables(&self) -> BTreeSet<String> {
+        self.terms
+            .keys()
+            .flat_map(|m| m.iter().map(|(v, _)| v.clone()))
+            .collect()
+    }
+
+    fn is_exact(&self) -> bool {
+        self.terms.values().all(is_exact)
+    }
+
+    fn sorted_terms(&self) -> Vec<(&Monomial, &Parameters)> {
+        let mut terms: Vec<(&Monomial, &Parameters)> = self.terms.iter().collect();
+        terms.sort_by(|(a, _), (b, _)| graded_order(b, a));
+        terms
+    }
+
+    fn leading(&self) -> Option<(Monomial, Parameters)> {
+        self.sorted_terms()
+            .first()
+            .map(|(m, c)| ((*m).clone(), (*c).clone()))
+    }
+
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let mut p = self.clone();
+        for (m, c) in &other.terms {
+            p.insert(m.clone(), c.clone());
+        }
+        p
+    }
+
+    pub fn scale(&self, c: &Parameters) -> Polynomial {
+        let mut p = Polynomial::zero();
+        for (m, c2) in &self.terms {
+            p.insert(m.clone(), c_mult(c, c2));
+        }
+        p
+    }
+
+    pub fn neg(&self) -> Polynomial {
+        self.scale(&Parameters::Int(-1))
+    }
+
+    fn mult_term(&self, m: &Monomial, c: &Parameters) -> Option<Polynomial> {
+        let mut p = Polynomial::zero();
+        for (m2, c2) in &self.terms {
+            p.insert(monomial_mult(m, m2)?, c_mult(c, c2));
+        }
+        Some(p)
+    }
+
+    pub fn mult(&self, other: &Polynomial) -> Result<Polynomial, Parameters> {
+        if self.terms.len() * other.terms.len() > MAX_TERM_PRODUCTS {
+            return Err(too_large());
+        }
+        let mut p = Polynomial::zero();
+        for (m, c) in &other.terms {
+            p = p.add(&self.mult_term(m, c).ok_or_else(too_large)?);
+        }
+        Ok(p)
+    }
+
+    pub fn pow(&self, mut n: u32) -> Result<Polynomial, Parameters> {
+        let half = n as usize / 2 + 1;
+        if self.terms.len() > 1 && half.saturating_mul(half) > MAX_TERM_PRODUCTS {
+            return Err(too_large());
+        }
+        let mut result = Polynomial::constant(Parameters::Int(1));
+        let mut base = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.mult(&base)?;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = base.mult(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn monomial_content(&self) -> Monomial {
+        let mut terms = self.terms.keys();
+        let mut content = match terms.next() {
+            Some(m) => m.clone(),
+            None => return vec![],
+        };
+        for m in terms {
+            content = content
+                .into_iter()
+                .filter_map(|(v, e)| match exponent(m, &v).min(e) {
+                    0 => None,
+                    e => Some((v, e)),
+                })
+                .collect();
+        }
+        content
+    }
+
+    fn divide_monomial(&self, m: &Monomial) -> Polynomial {
+        let mut p = Polynomial::zero();
+        for (m2, c) in &self.terms {
+            p.insert(monomial_divide(m2, m).unwrap_or_default(), c.clone());
+        }
+        p
+    }
+
+    fn exact_divide(&self, d: &Polynomial) -> Option<Polynomial> {
+        let (lm, lc) = d.leading()?;
+        let mut q = Polynomial::zero();
+        let mut r = self.clone();
+        while let Some((m, c)) = r.leading() {
+            let t = monomial_divide(&m, &lm)?;
+            let c = c_divide(&c, &lc);
+            r = r.add(&d.mult_term(&t, &c)?.neg());
+            q.insert(t, c);
+        }
+        Some(q)
+    }
+
+    fn degree_in(&self, var: &str) -> u32 {
+        self.terms
+            .keys()
+            .map(|m| exponent(m, var))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn coefficient_of(&self, m: &Monomial) -> Parameters {
+        self.terms.get(m).cloned().unwrap_or(Parameters::Int(0))
+    }
+
+    fn remainder(&self, d: &Polynomial, var: &str) -> Option<Polynomial> {
+        let dd = d.degree_in(var);
+        let lc = d.coefficient_of(&power_of(var, dd));
+        let mut r = self.clone();
+        while !r.is_zero() && r.degree_in(var) >= dd {
+            let dr = r.degree_in(var);
+            let c = c_divide(&r.coefficient_of(&power_of(var, dr)), &lc);
+            r = r.add(&d.mult_term(&power_of(var, dr - dd), &c)?.neg());
+        }
+        Some(r)
+    }
+
+    fn monic(&self) -> Polynomial {
+        match self.leading() {
+            Some((_, lc)) => self.scale(&c_divide(&Parameters::Int(1), &lc)),
+            None => self.clone(),
+        }
+    }
+
+    fn gcd(&self, other: &Polynomial, var: &str) -> Option<Polynomial> {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.remainder(&b, var)?;
+            a = b;
+            b = r;
+        }
+        Some(a.monic())
+    }
+
+    pub fn roots(&self, var: &str) -> Vec<Parameters> {
+        self.roots_with_multiplicity(var)
+            .into_iter()
+            .map(|(r, _)| r)
+            .collect()
+    }
+
+    pub fn roots_with_multiplicity(&self, var: &str) -> Vec<(Parameters, u32)> {
+        let (_, factors) = factor_polynomial(self);
+        let mut roots: Vec<(Parameters, u32)> = factors
+            .iter()
+            .flat_map(|(f, m)| factor_roots(f, var).into_iter().map(|r| (r, *m)))
+            .collect();
+        let key = |p: &Parameters| match p {
+            Parameters::Complex(c) => (1, c.re, c.im),
+            p => (0, p.to_f64().unwrap_or(f64::NAN), 0.0),
+        };
+        roots.sort_by(|(a, _), (b, _)| key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal));
+        roots
+    }
+
+    fn integer_content(&self) -> Option<(Rationals, Polynomial)> {
+        if !self.is_exact() || self.is_zero() {
+            return None;
+        }
+        let mut num = BigInt::zero();
+        let mut den = BigInt::one();
+        for c in self.terms.values() {
+            let r = c.to_rational()?.reduce();
+            num = num.gcd(&r.over);
+            den = den.lcm(&r.under);
+        }
+        let (_, lc) = self.leading()?;
+        if is_negative(&lc) {
+            num = -num;
+        }
+        let content = Rationals::from_big(den, num);
+        let primitive = self.scale(&c_divide(
+            &Parameters::Int(1),
+            &Parameters::from_rational(content.clone()),
+        ));
+        Some((content, primitive))
+    }
+
+    pub fn to_ast(&self) -> Ast {
+        let mut acc: Option<Ast> = None;
+        for (m, c) in self.sorted_terms() {
+            let negative = acc.is_some() && is_negative(c);
+            let c = if negative {
+                c_mult(&Parameters::Int(-1), c)
+            } else {
+                c.clone()
+            };
+            let mut term: Option<Ast> = None;
+            for (v, e) in m {
+                let factor = match e {
+                    1 => Ast::new(Parameters::Identifier(v.clone())),
+                    e => binary(
+                        Parameters::ExpoOperation,
+                        Ast::new(Parameters::Identifier(v.clone())),
+                        Ast::new(Parameters::Int(*e as i64)),
+                    ),
+                };
+                term = Some(match term {
+                    None => factor,
+                    Some(t) => binary(Parameters::MultiplicationOperation, t, factor),
+                });
+            }
+            let term = match term {
+                None => Ast::new(c),
+                Some(t) if is_one(&c) => t,
+                Some(t) if c.to_f64() == Some(-1.0) => {
+                    binary(Parameters::MinusOperation, t, Ast::Nil)
+                }
+                Some(t) => binary(Parameters::MultiplicationOperation, Ast::new(c), t),
+            };
+            acc = Some(match acc {
+                None => term,
+                Some(a) if negative => binary(Parameters::MinusOperation, a, term),
+                Some(a) => binary(Parameters::PlusOperation, a, term),
+            });
+        }
+        acc.unwrap_or(Ast::new(Parameters::Int(0)))
+    }
+
+    fn is_single_factor(&self) -> bool {
+        match self.sorted_terms().as_slice() {
+            [(m, c)] => m.len() <= 1 && (m.is_empty() || is_one(c)),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        for (i, (m, c)) in self.sorted_terms().into_iter().enumerate() {
+            let sign = match (i, is_negative(c)) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+            let c = if is_negative(c) {
+                c_mult(&Parameters::Int(-1), c)
+            } else {
+                c.clone()
+            };
+            write!(f, "{sign}{}", term_to_string(&c, m))?;
+        }
+        Ok(())
+    }
+}
+
+fn binary(op: Parameters, l: Ast, r: Ast) -> Ast {
+    Ast::Node {
+        value: op,
+        left: Box::new(l),
+        right: Box::new(r),
+        position: Position::default(),
+    }
+}
+
+fn wrapped(p: &Polynomial) -> String {
+    if p.is_single_factor() {
+        p.to_string()
+    } else {
+        format!("({p})")
+    }
+}
+
+fn factors_to_string(factors: &[(Polynomial, u32)]) -> String {
+    let mut s = String::new();
+    for (i, (p, e)) in factors.iter().enumerate() {
+        if i > 0 && (p.is_single_factor() || !s.ends_with(')')) {
+            s.push('*');
+        }
+        s.push_str(&wrapped(p));
+        if *e > 1 {
+            s.push_str(&format!("^{e}"));
+        }
+    }
+    s
+}
+
+fn product_to_string(c: &Parameters, factors: &[(Polynomial, u32)]) -> (String, usize) {
+    let body = factors_to_string(factors);
+    let count = factors.len() + usize::from(!is_one(c) && c.to_f64() != Some(-1.0));
+    let s = match (body.as_str(), coefficient_to_string(c).as_str()) {
+        ("", c) => c.to_string(),
+        (b, "1") => b.to_string(),
+        (b, "-1") => format!("-{b}"),
+        (b, c) => format!("{c}{b}"),
+    };
+    (s, count)
+}
+
+impl Display for Factors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (p, q) = match &self.constant {
+            Parameters::Rational(r) => {
+                let r = r.clone().reduce();
+                (
+                    Parameters::from_big_int(r.over),
+                    Parameters::from_big_int(r.under),
+                )
+            }
+            c => (c.clone(), Parameters::Int(1)),
+        };
+        if let ([(factor, 1)], true, true) = (self.num.as_slice(), is_one(&p), is_one(&q)) {
+            if self.den.is_empty() {
+                return write!(f, "{factor}");
+            }
+        }
+        let (num, _) = product_to_string(&p, &self.num);
+        match product_to_string(&q, &self.den) {
+            (_, 0) => write!(f, "{num}"),
+            (den, 1) => write!(f, "{num}/{den}"),
+            (den, _) => write!(f, "{num}/({den})"),
+        }
+    }
+}
+
+impl Expression {
+    pub fn constant(c: Parameters) -> Self {
+        Expression::from_polynomial(Polynomial::constant(c))
+    }
+
+    pub fn variable(name: &str) -> Self {
+        Expression::from_polynomial(Polynomial::variable(name))
+    }
+
+    pub fn from_polynomial(p: Polynomial) -> Self {
+        Expression {
+            num: p,
+            den: Polynomial::constant(Parameters::Int(1)),
+            factors: None,
+        }
+    }
+
+    fn reduce(num: Polynomial, den: Polynomial) -> Self {
+        if num.is_zero() {
+            return Expression::constant(Parameters::Int(0));
+        }
+        let m = Polynomial {
+            terms: num
+                .terms
+                .clone()
+                .into_iter()
+                .chain(den.terms.clone())
+                .collect(),
+        }
+        .monomial_content();
+        let (mut num, mut den) = (num.divide_monomial(&m), den.divide_monomial(&m));
+
+        if den.constant_value().is_none() {
+            if let Some(q) = num.exact_divide(&den) {
+                num = q;
+                den = Polynomial::constant(Parameters::Int(1));
+            } else if let Some(q) = den.exact_divide(&num) {
+                num = Polynomial::constant(Parameters::Int(1));
+                den = q;
+            } else {
+                let vars: BTreeSet<String> =
+                    num.variables().union(&den.variables()).cloned().collect();
+                if let (1, true) = (vars.len(), num.is_exact() && den.is_exact()) {
+                    let var = vars.into_iter().next().unwrap_or_default();
+                    if let Some(g) = num.gcd(&den, &var).filter(|g| g.degree_in(&var) > 0) {
+                        num = num.exact_divide(&g).unwrap_or(num);
+                        den = den.exact_divide(&g).unwrap_or(den);
+                    }
+                }
+            }
+        }
+
+        match den.integer_content() {
+            Some((content, primitive)) if den.constant_value().is_none() && num.is_exact() => {
+                num = num.scale(&c_divide(
+                    &Parameters::Int(1),
+                    &Parameters::from_rational(content),
+                ));
+                let mut lcm = BigInt::one();
+                for c in num.terms.values() {
+                    if let Some(r) = c.to_rational() {
+                        lcm = lcm.lcm(&r.reduce().under);
+                    }
+                }
+                let lcm = Parameters::from_big_int(lcm);
+                num = num.scale(&lcm);
+                den = primitive.scale(&lcm);
+            }
+            _ => {
+                if let Some((_, lc)) = den.leading() {
+                    let inverse = c_divide(&Parameters::Int(1), &lc);
+                    num = num.scale(&inverse);
+                    den = den.scale(&inverse);
+                }
+            }
+        }
+        Expression {
+            num,
+            den,
+            factors: None,
+        }
+    }
+
+    pub fn add(&self, other: &Expression) -> Result<Expression, Parameters> {
+        if self.den == other.den {
+            return Ok(Expression::reduce(
+                self.num.add(&other.num),
+                self.den.clone(),
+            ));
+        }
+        Ok(Expression::reduce(
+            self.num.mult(&other.den)?.add(&other.num.mult(&self.den)?),
+            self.den.mult(&other.den)?,
+        ))
+    }
+
+    pub fn neg(&self) -> Expression {
+        Expression {
+            num: self.num.neg(),
+            den: self.den.clone(),
+            factors: None,
+        }
+    }
+
+    pub fn minus(&self, other: &Expression) -> Result<Expression, Parameters> {
+        self.add(&other.neg())
+    }
+
+    pub fn mult(&self, other: &Expression) -> Result<Expression, Parameters> {
+        Ok(Expression::reduce(
+            self.num.mult(&other.num)?,
+            self.den.mult(&other.den)?,
+        ))
+    }
+
+    pub fn divide(&self, other: &Expression) -> Result<Expression, Parameters> {
+        if other.num.is_zero() {
+            return Err(error(
+                ErrorKind::DivisionByZero,
+                "Division by zero".to_string(),
+            ));
+        }
+        Ok(Expression::reduce(
+            self.num.mult(&other.den)?,
+            self.den.mult(&other.num)?,
+        ))
+    }
+
+    pub fn pow(&self, n: i64) -> Result<Expression, Parameters> {
+        let e = u32::try_from(n.unsigned_abs()).map_err(|_| {
+            error(
+                ErrorKind::Overflow,
+                format!("The exponent {n} is too large"),
+            )
+        })?;
+        let p = Expression::reduce(self.num.pow(e)?, self.den.pow(e)?);
+        if n < 0 {
+            Expression::constant(Parameters::Int(1)).divide(&p)
+        } else {
+            Ok(p)
+        }
+    }
+
+    pub fn variables(&self) -> BTreeSet<String> {
+        self.num
+            .variables()
+            .union(&self.den.variables())
+            .cloned()
+            .collect()
+    }
+
+    pub fn expanded(&self) -> Expression {
+        Expression {
+            factors: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn factor(&self) -> Expression {
+        let (c1, num) = factor_polynomial(&self.num);
+        let (c2, den) = factor_polynomial(&self.den);
+        Expression {
+            factors: Some(Factors {
+                constant: c_divide(&c1, &c2),
+                num,
+                den,
+            }),
+            ..self.clone()
+        }
+    }
+
+    pub fn into_parameters(self) -> Parameters {
+        match (self.num.constant_value(), self.den.constant_value()) {
+            (Some(n), Some(d)) => c_divide(&n, &d),
+            _ => Parameters::Symbolic(Box::new(self)),
+        }
+    }
+
+    pub fn to_ast(&self) -> Ast {
+        let product = |factors: &[(Polynomial, u32)], init: Option<Ast>| {
+            factors.iter().fold(init, |acc, (p, e)| {
+                let f = match e {
+                    1 => p.to_ast(),
+                    e => binary(
+                        Parameters::ExpoOperation,
+                        p.to_ast(),
+                        Ast::new(Parameters::Int(*e as i64)),
+                    ),
+                };
+                Some(match acc {
+                    None => f,
+                    Some(a) => binary(Parameters::MultiplicationOperation, a, f),
+                })
+            })
+        };
+        let (num, den) = match &self.factors {
+            Some(f) => {
+                let constant = match &f.constant {
+                    c if is_one(c) => None,
+                    c => Some(Ast::new(c.clone())),
+                };
+                (
+                    product(&f.num, constant).unwrap_or(Ast::new(Parameters::Int(1))),
+                    product(&f.den, None),
+                )
+            }
+            None => (
+                self.num.to_ast(),
+                match self.den.constant_value() {
+                    Some(_) => None,
+                    None => Some(self.den.to_ast()),
+                },
+            ),
+        };
+        match den {
+            None => num,
+            Some(d) => binary(Parameters::DivideOperation, num, d),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(factors) = &self.factors {
+            return write!(f, "{factors}");
+        }
+        match self.den.constant_value() {
+            Some(_) => write!(f, "{}", self.num),
+            None => write!(f, "{}/{}", wrapped(&self.num), wrapped(&self.den)),
+        }
+    }
+}
+
+fn divisors(n: &BigInt) -> Option<Vec<i64>> {
+    let n = n.abs().to_i64()?;
+    if n > MAX_ROOT_CANDIDATE {
+        return None;
+    }
+    Some((1..=n).filter(|d| n % d == 0).collect())
+}
+
+fn rational_root(p: &Polynomial, var: &str) -> Option<(i64, i64)> {
+    let low = p.terms.keys().map(|m| exponent(m, var)).min()?;
+    let high = p.degree_in(var);
+    let lc = p.coefficient_of(&power_of(var, high)).to_rational()?.over;
+    let c0 = p.coefficient_of(&power_of(var, low)).to_rational()?.over;
+    for q in divisors(&lc)? {
+        for a in divisors(&c0)? {
+            for a in [a, -a] {
+                if a.gcd(&q) != 1 {
+                    continue;
+                }
+                let root = Parameters::Rational(Rationals::new(q, a));
+                let value = p.terms.iter().fold(Parameters::Int(0), |acc, (m, c)| {
+                    let x =
+                        (0..exponent(m, var)).fold(Parameters::Int(1), |x, _| c_mult(&x, &root));
+                    c_add(&acc, &c_mult(c, &x))
+                });
+                if is_zero(&value) {
+                    return Some((a, q));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn factor_polynomial(p: &Polynomial) -> (Parameters, Vec<(Polynomial, u32)>) {
+    let (constant, mut rest) = match p.integer_content() {
+        Some((c, rest)) => (Parameters::from_rational(c), rest),
+        None => match p.leading() {
+            Some((_, lc)) => (lc.clone(), p.scale(&c_divide(&Parameters::Int(1), &lc))),
+            None => return (Parameters::Int(0), vec![]),
+        },
+    };
+    let mut factors = Vec::new();
+
+    let m = rest.monomial_content();
+    rest = rest.divide_monomial(&m);
+    for (v, e) in m {
+        factors.push((Polynomial::variable(&v), e));
+    }
+
+    let vars = rest.variables();
+    if vars.len() == 1 && rest.is_exact() {
+        let var = vars.into_iter().next().unwrap_or_default();
+        while rest.degree_in(&var) > 0 {
+            let (a, q) = match rational_root(&rest, &var) {
+                Some(r) => r,
+                None => break,
+            };
+            let linear = Polynomial::variable(&var)
+                .scale(&Parameters::Int(q))
+                .add(&Polynomial::constant(Parameters::Int(-a)));
+            let mut multiplicity = 0;
+            while let Some(q) = rest.exact_divide(&linear) {
+                if !q.is_exact() {
+                    break;
+                }
+                rest = q;
+                multiplicity += 1;
+            }
+            if multiplicity == 0 {
+                break;
+            }
+            factors.push((linear, multiplicity));
+        }
+    }
+    if rest.constant_value().is_none() {
+        factors.push((rest, 1));
+    }
+    factors.sort_by(|(a, _), (b, _)| {
+        (a.terms.len(), a.to_string()).cmp(&(b.terms.len(), b.to_string()))
+    });
+    (constant, factors)
+}
+
+fn numeric_roots(coefficients: &[Complex64]) -> Vec<Complex64> {
+    let n = coefficients.len() - 1;
+    let lc = coefficients[n];
+    let monic: Vec<Complex64> = coefficients.iter().map(|c| c / lc).collect();
+    let eval = |z: Complex64| {
+        monic
+            .iter()
+            .rev()
+            .fold(Complex64::zero(), |acc, c| acc * z + c)
+    };
+    let seed = Complex64::new(0.4, 0.9);
+    let mut z: Vec<Complex64> = (0..n).map(|k| seed.powu(k as u32)).collect();
+    for _ in 0..MAX_ROOT_ITERATIONS {
+        let mut change: f64 = 0.0;
+        for k in 0..n {
+            let denominator = (0..n)
+                .filter(|j| *j != k)
+                .fold(Complex64::one(), |acc, j| acc * (z[k] - z[j]));
+            let step = eval(z[k]) / denominator;
+            if step.is_finite() {
+                z[k] -= step;
+                change = change.max(step.norm() / z[k].norm().max(1.0));
+            }
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+    z
+}
+
+fn root_value(c: Complex64) -> Parameters {
+    if c.im.abs() <= 1e-10 * c.re.abs().max(1.0) {
+        Parameters::Float(c.re)
+    } else {
+        Parameters::Complex(c)
+    }
+}
+
+fn factor_roots(p: &Polynomial, var: &str) -> Vec<Parameters> {
+    let n = p.degree_in(var);
+    if n == 1 {
+        let c0 = p.coefficient_of(&power_of(var, 0));
+        let c1 = p.coefficient_of(&power_of(var, 1));
+        return vec![c_divide(&c_mult(&Parameters::Int(-1), &c0), &c1)];
+    }
+    let coefficients: Option<Vec<Complex64>> = (0..=n)
+        .map(|e| to_complex(&p.coefficient_of(&power_of(var, e))))
+        .collect();
+    let coefficients = match coefficients {
+        Some(c) => c,
+        None => return vec![],
+    };
+    let roots = if n == 2 {
+        let (c, b, a) = (coefficients[0], coefficients[1], coefficients[2]);
+        let delta = (b * b - 4.0 * a * c).sqrt();
+        vec![(-b - delta) / (2.0 * a), (-b + delta) / (2.0 * a)]
+    } else {
+        numeric_roots(&coefficients)
+    };
+    roots.into_iter().map(root_value).collect()
+}
+
+fn is_free(s: &str, ram: Option<&HashMap<String, Parameters>>) -> bool {
+    !s.starts_with('@') && !ram.is_some_and(|r| r.contains_key(s))
+}
+
+pub fn free_variable(p: Parameters) -> Parameters {
+    match p {
+        Parameters::Identifier(s) if !s.starts_with('@') => {
+            Parameters::Symbolic(Box::new(Expression::variable(&s)))
+        }
+        p => p,
+    }
+}
+
+pub fn symbolic_value(
+    p: &Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Option<Expression> {
+    match p {
+        Parameters::Symbolic(e) => Some(*e.clone()),
+        Parameters::Identifier(s) if is_free(s, ram) => Some(Expression::variable(s)),
+        _ => None,
+    }
+}
+
+fn to_expression(p: &Parameters, ram: Option<&HashMap<String, Parameters>>) -> Option<Expression> {
+    match p {
+        Parameters::Null => Some(Expression::constant(Parameters::Int(0))),
+        p if is_coefficient(p) => Some(Expression::constant(p.clone())),
+        p => symbolic_value(p, ram),
+    }
+}
+
+pub fn symbolic_operands(
+    p: &Parameters,
+    p2: &Parameters,
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Option<(Expression, Expression)> {
+    if symbolic_value(p, ram).is_none() && symbolic_value(p2, ram).is_none() {
+        return None;
+    }
+    Some((to_expression(p, ram)?, to_expression(p2, ram)?))
+}
@@ -1 +1,3 @@
+pub mod algebra;
 pub mod rationals;
+pub mod symbolic;
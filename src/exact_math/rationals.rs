@@ -1,91 +1,95 @@
 use std::{fmt::Display, ops};
 
-use crate::utils::integer_utils::gcd;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
 
 #[derive(Debug, Clone)]
 pub struct Rationals {
-    pub under: i64,
-    pub over: i64,
+    pub under: BigInt,
+    pub over: BigInt,
 }
 
 impl Rationals {
     pub fn put_to_denominator(&self, n: i64) -> Self {
         Rationals {
-            under: self.under * n,
-            over: self.over * n,
+            under: &self.under * n,
+            over: &self.over * n,
         }
     }
     pub fn new(under: i64, over: i64) -> Self {
+        Rationals {
+            under: BigInt::from(under),
+            over: BigInt::from(over),
+        }
+    }
+
+    pub fn from_big(under: BigInt, over: BigInt) -> Self {
         Rationals { under, over }
     }
 
     pub fn approx(self) -> f64 {
-        return self.over as f64 / self.under as f64;
+        match (self.over.to_f64(), self.under.to_f64()) {
+            (Some(o), Some(u)) if o.is_finite() && u.is_finite() => o / u,
+            _ => {
+                let (q, r) = self.over.div_rem(&self.under);
+                q.to_f64().unwrap_or(f64::NAN) + Rationals::from_big(self.under, r).approx()
+            }
+        }
     }
 
     pub fn rationalize(f: f64) -> Self {
-        let r = (f * (10.0_f64.powf(10.0))).round() as i64;
-        Rationals::new(10_i64.pow(10), r).reduce()
+        let whole = match BigInt::from_f64(f.trunc()) {
+            Some(w) => w,
+            None => return Rationals::new(1, 0),
+        };
+        let r = ((f - f.trunc()) * (10.0_f64.powf(10.0))).round() as i64;
+        (Rationals::from_big(BigInt::from(1), whole) + Rationals::new(10_i64.pow(10), r)).reduce()
     }
 
     pub fn is_null(self) -> bool {
-        return self.over == 0;
+        return self.over.is_zero();
     }
 
-    pub fn reduce(self) -> Self {
-        let minus;
-        let i1;
-        let i2;
-        if self.over < 0 && self.under > 0 {
-            minus = true;
-            i1 = -self.over;
-            i2 = self.under;
-        } else if self.over > 0 && self.under < 0 {
-            minus = true;
-            i1 = self.over;
-            i2 = -self.under;
-        } else if self.over < 0 && self.under < 0 {
-            minus = false;
-            i1 = -self.over;
-            i2 = -self.under;
+    pub fn to_int(&self) -> Option<i64> {
+        self.to_big_int().and_then(|i| i.to_i64())
+    }
+
+    pub fn to_big_int(&self) -> Option<BigInt> {
+        let fs = self.clone().reduce();
+        if fs.under == BigInt::from(1) {
+            Some(fs.over)
         } else {
-            minus = false;
-            i1 = self.over;
-            i2 = self.under;
+            None
         }
+    }
 
-        if i1 == 0 && i2 == 0 {
-            return Rationals { under: 0, over: 0 };
-        } else if i1 == 0 {
-            return Rationals { under: 1, over: 0 };
-        } else if i2 == 0 {
-            return Rationals {
-                under: 1,
-                over: i64::MAX,
-            };
-        } else {
-            let gcd = gcd(i1, i2);
-            let new_under = self.under.abs() / gcd;
-            let new_over = if minus {
-                -self.over.abs() / gcd
-            } else {
-                self.over.abs() / gcd
-            };
-            return Rationals {
-                under: new_under,
-                over: new_over,
-            };
+    pub fn reduce(self) -> Self {
+        if self.over.is_zero() && self.under.is_zero() {
+            return Rationals::new(0, 0);
+        } else if self.over.is_zero() {
+            return Rationals::new(1, 0);
+        } else if self.under.is_zero() {
+            return Rationals::new(1, i64::MAX);
+        }
+        let minus = self.over.is_negative() != self.under.is_negative();
+        let gcd = self.over.gcd(&self.under);
+        let new_under = self.under.abs() / &gcd;
+        let new_over = self.over.abs() / &gcd;
+        Rationals {
+            under: new_under,
+            over: if minus { -new_over } else { new_over },
         }
     }
     pub fn abs(self) -> Self {
-        Rationals::new(self.under.abs(), self.over.abs())
+        Rationals::from_big(self.under.abs(), self.over.abs())
     }
 }
 
 impl Display for Rationals {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let fs = self.clone().reduce();
-        if fs.under == 1 {
+        if fs.under == BigInt::from(1) {
             write!(f, "{}", fs.over)
         } else {
             write!(f, "{}/{}", fs.over, fs.under)
@@ -98,9 +102,7 @@ impl PartialEq for Rationals {
         if self.under == other.under {
             return self.over == other.over;
         } else {
-            let i1 = self.put_to_denominator(other.under);
-            let i2 = other.put_to_denominator(self.under);
-            return i1.over == i2.over;
+            return &self.over * &other.under == &other.over * &self.under;
         }
     }
     fn ne(&self, other: &Self) -> bool {
@@ -110,31 +112,9 @@ impl PartialEq for Rationals {
 
 impl PartialOrd for Rationals {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if !(self.under == other.under) {
-            if self.over == other.over {
-                return Some(std::cmp::Ordering::Equal);
-            }
-            if self.over > other.over {
-                return Some(std::cmp::Ordering::Greater);
-            }
-            if self.over < other.over {
-                return Some(std::cmp::Ordering::Less);
-            }
-            None
-        } else {
-            let i1 = self.put_to_denominator(other.under);
-            let i2 = other.put_to_denominator(self.under);
-            if i1.over == i2.over {
-                return Some(std::cmp::Ordering::Equal);
-            }
-            if i1.over > i2.over {
-                return Some(std::cmp::Ordering::Greater);
-            }
-            if i1.over < i2.over {
-                return Some(std::cmp::Ordering::Less);
-            }
-            None
-        }
+        let i1 = self.clone().reduce();
+        let i2 = other.clone().reduce();
+        (&i1.over * &i2.under).partial_cmp(&(&i2.over * &i1.under))
     }
 
     fn le(&self, other: &Self) -> bool {
@@ -149,11 +129,10 @@ impl ops::Add for Rationals {
     type Output = Rationals;
     fn add(self, rhs: Self) -> Self::Output {
         if self.under == rhs.under {
-            Rationals::new(self.under, self.over + rhs.over).reduce()
+            Rationals::from_big(self.under, self.over + rhs.over).reduce()
         } else {
-            let f1 = self.put_to_denominator(rhs.under);
-            let f2 = rhs.put_to_denominator(self.under);
-            Rationals::new(f1.under, f1.over + f2.over).reduce()
+            let over = &self.over * &rhs.under + &rhs.over * &self.under;
+            Rationals::from_big(self.under * rhs.under, over).reduce()
         }
     }
 }
@@ -161,14 +140,14 @@ impl ops::Add for Rationals {
 impl ops::Sub for Rationals {
     type Output = Rationals;
     fn sub(self, rhs: Self) -> Self::Output {
-        return self + Rationals::new(rhs.under, -rhs.over);
+        return self + Rationals::from_big(rhs.under, -rhs.over);
     }
 }
 
 impl ops::Mul for Rationals {
     type Output = Rationals;
     fn mul(self, rhs: Self) -> Self::Output {
-        return Rationals::new(self.under * rhs.under, self.over * rhs.over).reduce();
+        return Rationals::from_big(self.under * rhs.under, self.over * rhs.over).reduce();
     }
 }
 
@@ -177,13 +156,15 @@ impl ops::Div for Rationals {
     fn div(self, rhs: Self) -> Self::Output {
         let l = self.under * rhs.over;
         let rs = self.over * rhs.under;
-        let r = Rationals::new(l, rs);
+        let r = Rationals::from_big(l, rs);
         return r.reduce();
     }
 }
 
 #[cfg(test)]
 mod test {
+    use num_bigint::BigInt;
+
     use super::Rationals;
 
     #[test]
@@ -281,4 +262,19 @@ mod test {
         let value = Rationals::new(25, 32) / Rationals::new(76, 42);
         assert_eq!(value, expected);
     }
+
+    #[test]
+    pub fn test_large_denominators() {
+        let expected = Rationals::from_big(
+            BigInt::from(i64::MAX) * (i64::MAX - 1),
+            BigInt::from(i64::MAX) + (i64::MAX - 1),
+        );
+        let value = Rationals::new(i64::MAX, 1) + Rationals::new(i64::MAX - 1, 1);
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    pub fn test_order_different_denominators() {
+        assert_eq!(Rationals::new(2, 1) > Rationals::new(3, 1), true);
+    }
 }
@@ -0,0 +1,605 @@
+use std::collections::HashMap;
+
+use crate::interpreting::error::{error, ErrorKind};
+use crate::interpreting::function::{add, divide, expo, minus, mult};
+use crate::parsing::ast::Parameters;
+use crate::parsing::ast::{Ast, Position};
+
+const MAX_INLINE_DEPTH: usize = 32;
+
+fn leaf(p: Parameters) -> Ast {
+    Ast::new(p)
+}
+
+fn num(i: i64) -> Ast {
+    leaf(Parameters::Int(i))
+}
+
+fn node(op: Parameters, l: Ast, r: Ast) -> Ast {
+    Ast::Node {
+        value: op,
+        left: Box::new(l),
+        right: Box::new(r),
+        position: Position::default(),
+    }
+}
+
+fn plus(l: Ast, r: Ast) -> Ast {
+    node(Parameters::PlusOperation, l, r)
+}
+
+fn sub(l: Ast, r: Ast) -> Ast {
+    node(Parameters::MinusOperation, l, r)
+}
+
+fn neg(a: Ast) -> Ast {
+    node(Parameters::MinusOperation, a, Ast::Nil)
+}
+
+fn times(l: Ast, r: Ast) -> Ast {
+    node(Parameters::MultiplicationOperation, l, r)
+}
+
+fn over(l: Ast, r: Ast) -> Ast {
+    node(Parameters::DivideOperation, l, r)
+}
+
+fn pow(l: Ast, r: Ast) -> Ast {
+    node(Parameters::ExpoOperation, l, r)
+}
+
+fn call(name: &str, arg: Ast) -> Ast {
+    Ast::Call {
+        name: name.to_string(),
+        lst: vec![arg],
+        position: Position::default(),
+    }
+}
+
+fn not_differentiable(ast: &Ast) -> Parameters {
+    error(
+        ErrorKind::IncompatibleTypes,
+        format!(
+            "The expression {} can not be differentiated",
+            ast.to_expression()
+        ),
+    )
+}
+
+pub fn depends_on(ast: &Ast, var: &str) -> bool {
+    match ast {
+        Ast::Nil => false,
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            ..
+        } => {
+            let in_value = match v {
+                Parameters::Identifier(s) => s == var,
+                Parameters::Vector(a) => a.iter().any(|x| depends_on(x, var)),
+                Parameters::Lambda(params, body) => {
+                    !params.iter().any(|p| p == var) && depends_on(body, var)
+                }
+                _ => false,
+            };
+            in_value || depends_on(l, var) || depends_on(r, var)
+        }
+        Ast::Call { lst: l, .. } => l.iter().any(|x| depends_on(x, var)),
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            ..
+        } => depends_on(c, var) || depends_on(t, var) || depends_on(o, var),
+        Ast::Index {
+            target: t,
+            indices: i,
+            ..
+        } => depends_on(t, var) || i.iter().any(|x| depends_on(x, var)),
+    }
+}
+
+pub fn substitute(ast: &Ast, bindings: &HashMap<String, Ast>) -> Ast {
+    match ast {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => match bindings.get(s) {
+            Some(a) => a.clone(),
+            None => ast.clone(),
+        },
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            position: p,
+        } => Ast::Node {
+            value: v.clone(),
+            left: Box::new(substitute(l, bindings)),
+            right: Box::new(substitute(r, bindings)),
+            position: *p,
+        },
+        Ast::Call {
+            name: n,
+            lst: l,
+            position: p,
+        } => Ast::Call {
+            name: n.clone(),
+            lst: l.iter().map(|x| substitute(x, bindings)).collect(),
+            position: *p,
+        },
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            position: p,
+        } => Ast::If {
+            condition: Box::new(substitute(c, bindings)),
+            then: Box::new(substitute(t, bindings)),
+            otherwise: Box::new(substitute(o, bindings)),
+            position: *p,
+        },
+        Ast::Index {
+            target: t,
+            indices: i,
+            position: p,
+        } => Ast::Index {
+            target: Box::new(substitute(t, bindings)),
+            indices: i.iter().map(|x| substitute(x, bindings)).collect(),
+            position: *p,
+        },
+        Ast::Nil => Ast::Nil,
+    }
+}
+
+pub fn inline_call(
+    name: &str,
+    args: &Vec<Ast>,
+    functions: &HashMap<String, (Vec<Ast>, Ast)>,
+) -> Option<Ast> {
+    let (params, body) = functions.get(name)?;
+    let mut bindings = HashMap::new();
+    for (p, a) in params.iter().zip(args) {
+        if let Ast::Node {
+            value: Parameters::Identifier(s),
+            ..
+        } = p
+        {
+            bindings.insert(s.to_string(), a.clone());
+        }
+    }
+    Some(substitute(body, &bindings))
+}
+
+fn derivative_of_call(name: &str, u: &Ast) -> Option<Ast> {
+    let u = u.clone();
+    Some(match name {
+        "sin" => call("cos", u),
+        "cos" => neg(call("sin", u)),
+        "tan" => over(num(1), pow(call("cos", u), num(2))),
+        "sinh" => call("cosh", u),
+        "cosh" => call("sinh", u),
+        "tanh" => over(num(1), pow(call("cosh", u), num(2))),
+        "exp" => call("exp", u),
+        "ln" | "log" => over(num(1), u),
+        "sqrt" => over(num(1), times(num(2), call("sqrt", u))),
+        "asin" => over(num(1), call("sqrt", sub(num(1), pow(u, num(2))))),
+        "acos" => neg(over(num(1), call("sqrt", sub(num(1), pow(u, num(2)))))),
+        "atan" => over(num(1), plus(num(1), pow(u, num(2)))),
+        "abs" => over(u.clone(), call("abs", u)),
+        _ => return None,
+    })
+}
+
+fn derive(
+    ast: &Ast,
+    var: &str,
+    functions: &HashMap<String, (Vec<Ast>, Ast)>,
+    depth: usize,
+) -> Result<Ast, Parameters> {
+    if !depends_on(ast, var) {
+        return Ok(num(0));
+    }
+    match ast {
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            ..
+        } => match (v, l.as_ref(), r.as_ref()) {
+            (Parameters::Identifier(_), Ast::Nil, Ast::Nil) => Ok(num(1)),
+            (Parameters::PlusOperation, l, Ast::Nil) => derive(l, var, functions, depth),
+            (Parameters::MinusOperation, l, Ast::Nil) => Ok(neg(derive(l, var, functions, depth)?)),
+            (Parameters::PlusOperation, l, r) => Ok(plus(
+                derive(l, var, functions, depth)?,
+                derive(r, var, functions, depth)?,
+            )),
+            (Parameters::MinusOperation, l, r) => Ok(sub(
+                derive(l, var, functions, depth)?,
+                derive(r, var, functions, depth)?,
+            )),
+            (Parameters::MultiplicationOperation, l, r) => Ok(plus(
+                times(derive(l, var, functions, depth)?, r.clone()),
+                times(l.clone(), derive(r, var, functions, depth)?),
+            )),
+            (Parameters::DivideOperation, l, r) => Ok(over(
+                sub(
+                    times(derive(l, var, functions, depth)?, r.clone()),
+                    times(l.clone(), derive(r, var, functions, depth)?),
+                ),
+                pow(r.clone(), num(2)),
+            )),
+            (Parameters::ExpoOperation, l, r) if !depends_on(r, var) => Ok(times(
+                times(r.clone(), pow(l.clone(), sub(r.clone(), num(1)))),
+                derive(l, var, functions, depth)?,
+            )),
+            (Parameters::ExpoOperation, l, r) if !depends_on(l, var) => Ok(times(
+                times(ast.clone(), call("ln", l.clone())),
+                derive(r, var, functions, depth)?,
+            )),
+            (Parameters::ExpoOperation, l, r) => Ok(times(
+                ast.clone(),
+                plus(
+                    times(derive(r, var, functions, depth)?, call("ln", l.clone())),
+                    over(
+                        times(r.clone(), derive(l, var, functions, depth)?),
+                        l.clone(),
+                    ),
+                ),
+            )),
+            _ => Err(not_differentiable(ast)),
+        },
+        Ast::Call {
+            name: n, lst: l, ..
+        } if l.len() == 1 => match derivative_of_call(n, &l[0]) {
+            Some(d) => Ok(times(d, derive(&l[0], var, functions, depth)?)),
+            None => derive_user_call(ast, n, l, var, functions, depth),
+        },
+        Ast::Call {
+            name: n, lst: l, ..
+        } => derive_user_call(ast, n, l, var, functions, depth),
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            ..
+        } => Ok(Ast::If {
+            condition: c.clone(),
+            then: Box::new(derive(t, var, functions, depth)?),
+            otherwise: Box::new(derive(o, var, functions, depth)?),
+            position: Position::default(),
+        }),
+        _ => Err(not_differentiable(ast)),
+    }
+}
+
+fn derive_user_call(
+    ast: &Ast,
+    name: &str,
+    args: &Vec<Ast>,
+    var: &str,
+    functions: &HashMap<String, (Vec<Ast>, Ast)>,
+    depth: usize,
+) -> Result<Ast, Parameters> {
+    if depth >= MAX_INLINE_DEPTH {
+        return Err(error(
+            ErrorKind::RecursionLimit,
+            format!("The function {name} is too deeply nested to be differentiated"),
+        ));
+    }
+    match inline_call(name, args, functions) {
+        Some(body) => derive(&body, var, functions, depth + 1),
+        None => Err(not_differentiable(ast)),
+    }
+}
+
+pub fn differentiate(
+    ast: &Ast,
+    var: &str,
+    functions: &HashMap<String, (Vec<Ast>, Ast)>,
+) -> Result<Ast, Parameters> {
+    derive(ast, var, functions, 0).map(|d| simplify(&d))
+}
+
+fn number(ast: &Ast) -> Option<Parameters> {
+    match ast {
+        Ast::Node {
+            value: v @ (Parameters::Int(_) | Parameters::Rational(_) | Parameters::Float(_)),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => Some(v.clone()),
+        _ => None,
+    }
+}
+
+fn is_number(ast: &Ast, n: i64) -> bool {
+    match number(ast) {
+        Some(Parameters::Float(f)) => f == n as f64,
+        Some(p) => p.to_rational().and_then(|r| r.to_int()) == Some(n),
+        None => false,
+    }
+}
+
+fn integer(ast: &Ast) -> Option<i64> {
+    number(ast)
+        .and_then(|n| n.to_rational())
+        .and_then(|r| r.to_int())
+}
+
+fn fold(
+    op: fn(Parameters, Parameters, Option<&HashMap<String, Parameters>>) -> Parameters,
+    a: Parameters,
+    b: Parameters,
+) -> Option<Ast> {
+    match op(a, b, None) {
+        p @ Parameters::Int(_) => Some(leaf(p)),
+        Parameters::Rational(r) => Some(leaf(Parameters::from_rational(r))),
+        Parameters::Float(f) if f.is_finite() => Some(leaf(Parameters::Float(f))),
+        _ => None,
+    }
+}
+
+fn negate(a: Ast) -> Ast {
+    let (c, body) = split_coefficient(a);
+    match fold(mult, Parameters::Int(-1), c.clone()) {
+        Some(c) => with_coefficient(c, body),
+        None => neg(with_coefficient(leaf(c), body)),
+    }
+}
+
+fn split_coefficient(ast: Ast) -> (Parameters, Ast) {
+    if let Some(n) = number(&ast) {
+        return (n, num(1));
+    }
+    match ast {
+        Ast::Node {
+            value: Parameters::MultiplicationOperation,
+            left: l,
+            right: r,
+            ..
+        } if number(&l).is_some() => (number(&l).unwrap(), *r),
+        Ast::Node {
+            value: Parameters::DivideOperation,
+            left: l,
+            right: r,
+            ..
+        } if !is_number(&l, 1) => {
+            let (c, n) = split_coefficient(*l);
+            (c, over(n, *r))
+        }
+        Ast::Node {
+            value: Parameters::MinusOperation,
+            left: l,
+            right: r,
+            ..
+        } if *r == Ast::Nil => {
+            let (c, rest) = split_coefficient(*l);
+            match fold(mult, Parameters::Int(-1), c.clone()) {
+                Some(n) => (number(&n).unwrap(), rest),
+                None => (Parameters::Int(1), neg(times(leaf(c), rest))),
+            }
+        }
+        ast => (Parameters::Int(1), ast),
+    }
+}
+
+fn is_negative(ast: &Ast) -> bool {
+    match ast {
+        Ast::Node {
+            value: Parameters::MinusOperation,
+            right: r,
+            ..
+        } if **r == Ast::Nil => true,
+        _ => matches!(split_coefficient(ast.clone()).0.to_f64(), Some(f) if f < 0.0),
+    }
+}
+
+fn with_coefficient(c: Ast, body: Ast) -> Ast {
+    if let Some(Parameters::Rational(r)) = number(&c) {
+        let r = r.reduce();
+        let (p, q) = (
+            leaf(Parameters::from_big_int(r.over)),
+            leaf(Parameters::from_big_int(r.under)),
+        );
+        return match body {
+            Ast::Node {
+                value: Parameters::DivideOperation,
+                left: n,
+                right: d,
+                ..
+            } => over(with_coefficient(p, *n), with_coefficient(q, *d)),
+            body => over(with_coefficient(p, body), q),
+        };
+    }
+    match body {
+        _ if is_number(&c, 0) => num(0),
+        _ if is_number(&c, 1) => body,
+        Ast::Node {
+            value: Parameters::DivideOperation,
+            left: n,
+            right: d,
+            ..
+        } => {
+            let (cd, d) = split_coefficient(*d);
+            match fold(divide, number(&c).unwrap_or(Parameters::Int(1)), cd.clone()) {
+                Some(c2) if !is_number(&leaf(cd.clone()), 1) => with_coefficient(c2, over(*n, d)),
+                _ => over(with_coefficient(c, *n), with_coefficient(leaf(cd), d)),
+            }
+        }
+        _ if is_number(&body, 1) => c,
+        _ if is_number(&c, -1) => neg(body),
+        body => times(c, body),
+    }
+}
+
+pub fn simplify(ast: &Ast) -> Ast {
+    match ast {
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            ..
+        } if !(**l == Ast::Nil && **r == Ast::Nil) => {
+            let l = simplify(l);
+            let r = simplify(r);
+            simplify_node(v, l, r)
+        }
+        Ast::Call {
+            name: n, lst: l, ..
+        } => Ast::Call {
+            name: n.clone(),
+            lst: l.iter().map(simplify).collect(),
+            position: Position::default(),
+        },
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            ..
+        } => {
+            let (t, o) = (simplify(t), simplify(o));
+            if t == o {
+                t
+            } else {
+                Ast::If {
+                    condition: Box::new(simplify(c)),
+                    then: Box::new(t),
+                    otherwise: Box::new(o),
+                    position: Position::default(),
+                }
+            }
+        }
+        _ => ast.clone(),
+    }
+}
+
+fn simplify_node(v: &Parameters, l: Ast, r: Ast) -> Ast {
+    let (a, b) = (number(&l), number(&r));
+    match v {
+        Parameters::PlusOperation if r == Ast::Nil => l,
+        Parameters::MinusOperation if r == Ast::Nil => negate(l),
+        Parameters::PlusOperation => match (a, b) {
+            (Some(a), Some(b)) => fold(add, a, b).unwrap_or(plus(l, r)),
+            _ if is_number(&l, 0) => r,
+            _ if is_number(&r, 0) => l,
+            _ if is_negative(&r) => sub(l, negate(r)),
+            _ => plus(l, r),
+        },
+        Parameters::MinusOperation => match (a, b) {
+            (Some(a), Some(b)) => fold(minus, a, b).unwrap_or(sub(l, r)),
+            _ if l == r => num(0),
+            _ if is_number(&r, 0) => l,
+            _ if is_number(&l, 0) => negate(r),
+            _ if is_negative(&r) => plus(l, negate(r)),
+            _ => sub(l, r),
+        },
+        Parameters::MultiplicationOperation => match (a, b) {
+            (Some(a), Some(b)) => fold(mult, a, b).unwrap_or(times(l, r)),
+            _ if is_number(&l, 0) || is_number(&r, 0) => num(0),
+            _ => {
+                let ((c1, l), (c2, r)) = (split_coefficient(l), split_coefficient(r));
+                let body = match (l, r) {
+                    (l, r) if is_number(&l, 1) => r,
+                    (l, r) if is_number(&r, 1) => l,
+                    (l, r) if l == r => pow(l, num(2)),
+                    (
+                        l,
+                        Ast::Node {
+                            value: Parameters::DivideOperation,
+                            left: n,
+                            right: d,
+                            ..
+                        },
+                    ) => over(simplify_node(v, l, *n), *d),
+                    (
+                        Ast::Node {
+                            value: Parameters::DivideOperation,
+                            left: n,
+                            right: d,
+                            ..
+                        },
+                        r,
+                    ) => over(simplify_node(v, *n, r), *d),
+                    (l, r) => times(l, r),
+                };
+                match fold(mult, c1.clone(), c2.clone()) {
+                    Some(c) => with_coefficient(c, body),
+                    None => times(times(leaf(c1), leaf(c2)), body),
+                }
+            }
+        },
+        Parameters::DivideOperation => match (a, b) {
+            _ if is_number(&r, 0) => over(l, r),
+            (Some(a), Some(b)) => fold(divide, a, b).unwrap_or(over(l, r)),
+            _ if is_number(&l, 0) => num(0),
+            _ => {
+                let ((c1, l), (c2, r)) = (split_coefficient(l), split_coefficient(r));
+                let body = match (l, r) {
+                    (l, r) if is_number(&r, 1) => l,
+                    (l, r) if l == r => num(1),
+                    (
+                        Ast::Node {
+                            value: Parameters::DivideOperation,
+                            left: n,
+                            right: d,
+                            ..
+                        },
+                        r,
+                    ) => over(
+                        *n,
+                        simplify_node(&Parameters::MultiplicationOperation, *d, r),
+                    ),
+                    (l, r) => over(l, r),
+                };
+                match fold(divide, c1.clone(), c2.clone()) {
+                    Some(c) => with_coefficient(c, body),
+                    None => times(over(leaf(c1), leaf(c2)), body),
+                }
+            }
+        },
+        Parameters::ExpoOperation => match (a, b) {
+            (Some(a), Some(b)) => fold(expo, a, b).unwrap_or(pow(l, r)),
+            _ if is_number(&r, 0) => num(1),
+            _ if is_number(&r, 1) => l,
+            _ if is_number(&l, 1) => num(1),
+            _ => match l {
+                Ast::Call {
+                    name: n, lst: args, ..
+                } if n == "sqrt" && is_number(&r, 2) => args[0].clone(),
+                Ast::Node {
+                    value: Parameters::MultiplicationOperation,
+                    left: c,
+                    right: base,
+                    ..
+                } if number(&c).is_some() && integer(&r).is_some() => {
+                    match fold(expo, number(&c).unwrap(), number(&r).unwrap()) {
+                        Some(c) => simplify_node(
+                            &Parameters::MultiplicationOperation,
+                            c,
+                            simplify_node(v, *base, r),
+                        ),
+                        None => pow(times(*c, *base), r),
+                    }
+                }
+                Ast::Node {
+                    value: Parameters::ExpoOperation,
+                    left: base,
+                    right: e,
+                    ..
+                } => match (number(&e), number(&r)) {
+                    (Some(e1), Some(e2)) => match fold(mult, e1, e2) {
+                        Some(n) => simplify_node(v, *base, n),
+                        None => pow(pow(*base, *e), r),
+                    },
+                    _ => pow(pow(*base, *e), r),
+                },
+                l => pow(l, r),
+            },
+        },
+        _ => node(v.clone(), l, r),
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Display, Formatter};
+
+use crate::parsing::ast::Parameters;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    UnknownVariable,
+    UnknownFunction,
+    IncompatibleTypes,
+    WrongArguments,
+    DivisionByZero,
+    NotInvertible,
+    NotABoolean,
+    RecursionLimit,
+    OutOfBounds,
+    Overflow,
+    Syntax,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub expression: Option<String>,
+    pub position: Option<usize>,
+    pub span: Option<(usize, Option<usize>)>,
+}
+
+impl CalcError {
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        CalcError {
+            kind,
+            message,
+            expression: None,
+            position: None,
+            span: None,
+        }
+    }
+
+    pub fn with_position(self, position: usize) -> Self {
+        CalcError {
+            position: Some(position),
+            ..self
+        }
+    }
+
+    pub fn with_expression(self, expression: String) -> Self {
+        match self.expression {
+            Some(_) => self,
+            None => CalcError {
+                expression: Some(expression),
+                ..self
+            },
+        }
+    }
+
+    pub fn with_span(self, span: Option<(usize, Option<usize>)>) -> Self {
+        match self.span {
+            Some(_) => self,
+            None => CalcError { span, ..self },
+        }
+    }
+
+    pub fn with_source(self, line: &str) -> Self {
+        let chars: Vec<char> = line.chars().collect();
+        match self.span {
+            Some((start, end)) if start < chars.len() => {
+                let end = end.unwrap_or(chars.len()).min(chars.len());
+                let source: String = chars[start..end].iter().collect();
+                CalcError {
+                    expression: Some(source.trim_end().to_string()),
+                    span: None,
+                    ..self
+                }
+            }
+            _ => self,
+        }
+    }
+}
+
+pub fn error(kind: ErrorKind, message: String) -> Parameters {
+    Parameters::Error(CalcError::new(kind, message))
+}
+
+pub fn unknown_variable(name: &str) -> Parameters {
+    error(
+        ErrorKind::UnknownVariable,
+        format!("The variable {name} is not initialized yet"),
+    )
+}
+
+pub fn unknown_function(name: &str) -> Parameters {
+    error(
+        ErrorKind::UnknownFunction,
+        format!("The function {name} is unknown"),
+    )
+}
+
+pub fn incompatible_operator(operator: &str) -> Parameters {
+    error(
+        ErrorKind::IncompatibleTypes,
+        format!("Those two values are incompatible with the {operator} operator"),
+    )
+}
+
+pub fn wrong_arguments(function: &str) -> Parameters {
+    error(
+        ErrorKind::WrongArguments,
+        format!("The function {function} needs at least one argument"),
+    )
+}
+
+pub fn incompatible_argument(function: &str) -> Parameters {
+    error(
+        ErrorKind::IncompatibleTypes,
+        format!("The function {function} can not be applied to this value"),
+    )
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::UnknownVariable => write!(f, "unknown variable"),
+            ErrorKind::UnknownFunction => write!(f, "unknown function"),
+            ErrorKind::IncompatibleTypes => write!(f, "type error"),
+            ErrorKind::WrongArguments => write!(f, "argument error"),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::NotInvertible => write!(f, "math error"),
+            ErrorKind::NotABoolean => write!(f, "type error"),
+            ErrorKind::RecursionLimit => write!(f, "recursion error"),
+            ErrorKind::OutOfBounds => write!(f, "index error"),
+            ErrorKind::Overflow => write!(f, "overflow error"),
+            ErrorKind::Syntax => write!(f, "syntax error"),
+        }
+    }
+}
+
+impl Display for CalcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error ({}): {}", self.kind, self.message)?;
+        if let Some(p) = self.position {
+            write!(f, " at column {}", p + 1)?;
+        }
+        match &self.expression {
+            None => Ok(()),
+            Some(e) => write!(f, " in {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CalcError, ErrorKind};
+
+    #[test]
+    pub fn test_keep_innermost_expression() {
+        let error = CalcError::new(ErrorKind::DivisionByZero, "Division by zero".to_string())
+            .with_expression("(1 / 0)".to_string())
+            .with_expression("((1 / 0) + 1)".to_string());
+        assert_eq!(error.expression, Some("(1 / 0)".to_string()));
+    }
+
+    #[test]
+    pub fn test_display() {
+        let error = CalcError::new(ErrorKind::UnknownFunction, "f is unknown".to_string())
+            .with_expression("f(1)".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Error (unknown function): f is unknown in f(1)"
+        );
+    }
+
+    #[test]
+    pub fn test_display_position() {
+        let error =
+            CalcError::new(ErrorKind::Syntax, "unexpected ')'".to_string()).with_position(6);
+        assert_eq!(
+            error.to_string(),
+            "Error (syntax error): unexpected ')' at column 7"
+        );
+    }
+
+    #[test]
+    pub fn test_expression_from_source() {
+        let error = CalcError::new(ErrorKind::DivisionByZero, "Division by zero".to_string())
+            .with_expression("1 / 0".to_string())
+            .with_span(Some((5, Some(9))));
+        assert_eq!(
+            error.with_source("2 * (1/0 ) + 1").expression,
+            Some("1/0".to_string())
+        );
+    }
+}
@@ -1,8 +1,15 @@
 use std::collections::HashMap;
 
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_traits::ToPrimitive;
+
+use crate::exact_math::algebra::{free_variable, symbolic_operands, symbolic_value, Expression};
 use crate::exact_math::rationals::Rationals;
+use crate::interpreting::error::{error, incompatible_operator, unknown_variable, ErrorKind};
 use crate::parsing::ast::Parameters;
 use crate::parsing::ast::Parameters::Bool;
+use crate::utils::complex_utils::{complex_operands, complex_result, is_negative_real, to_complex};
 use crate::utils::matrix_utils::mult_matrix;
 
 pub fn apply_operator(
@@ -19,11 +26,14 @@ pub fn apply_operator(
         return Parameters::Null;
     }
     match ram {
-        None => value2,
+        None => unknown_variable(&s),
         Some(i_ram) => {
             let value = i_ram.get(&s);
             match value {
-                None => value2,
+                None => unknown_variable(&s),
+                Some(Parameters::Identifier(s2)) if *s2 == s => {
+                    f(free_variable(Parameters::Identifier(s)), value2, ram)
+                }
                 Some(val) => f(val.clone(), value2.clone(), ram),
             }
         }
@@ -44,11 +54,14 @@ pub fn apply_operator_reverse(
         return Parameters::Null;
     }
     match ram {
-        None => value,
+        None => unknown_variable(&s),
         Some(i_ram) => {
             let val3 = i_ram.get(&s);
             match val3 {
-                None => value,
+                None => unknown_variable(&s),
+                Some(Parameters::Identifier(s2)) if *s2 == s => {
+                    f(value, free_variable(Parameters::Identifier(s)), ram)
+                }
                 Some(val) => f(value.clone(), val.clone(), ram),
             }
         }
@@ -56,7 +69,16 @@ pub fn apply_operator_reverse(
 }
 
 pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
+    if let Some((a, b)) = symbolic_operands(&i, &i2, ram) {
+        return a.add(&b).map_or_else(|e| e, Expression::into_parameters);
+    }
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return complex_result(c + c2);
+    }
     match (i, i2) {
+        (Parameters::Complex(c), Parameters::Null) | (Parameters::Null, Parameters::Complex(c)) => {
+            Parameters::Complex(c)
+        }
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Null, Parameters::InterpreterVector(vec)) => {
@@ -78,7 +100,10 @@ pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameter
         }
         (Parameters::Rational(s), Parameters::Float(f)) => Parameters::Float(s.approx() + f),
         (Parameters::Float(f), Parameters::Rational(s)) => Parameters::Float(f + s.approx()),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v + v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_add(v2) {
+            Some(r) => Parameters::Int(r),
+            None => Parameters::from_big_int(BigInt::from(v) + v2),
+        },
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) + f),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v + f),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v + (i1 as f64)),
@@ -151,9 +176,7 @@ pub fn add(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameter
         (Parameters::Identifier(s), Bool(b)) => {
             apply_operator(Parameters::Identifier(s), Bool(b), ram, add)
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the + operator".to_string(),
-        ),
+        _ => incompatible_operator("+"),
     }
 }
 
@@ -162,12 +185,27 @@ pub fn minus(
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
 ) -> Parameters {
+    if let (Some(a), Parameters::Null) = (symbolic_value(&i, ram), &i2) {
+        return a.neg().into_parameters();
+    }
+    if let Some((a, b)) = symbolic_operands(&i, &i2, ram) {
+        return a.minus(&b).map_or_else(|e| e, Expression::into_parameters);
+    }
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return complex_result(c - c2);
+    }
     match (i, i2) {
+        (Parameters::Complex(c), Parameters::Null) | (Parameters::Null, Parameters::Complex(c)) => {
+            Parameters::Complex(-c)
+        }
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(-v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(-f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(-v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(-f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v - v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_sub(v2) {
+            Some(r) => Parameters::Int(r),
+            None => Parameters::from_big_int(BigInt::from(v) - v2),
+        },
 
         (Parameters::Rational(s), Parameters::Null) => {
             Parameters::Rational(Rationals::new(1, 0) - s)
@@ -249,21 +287,13 @@ pub fn minus(
             apply_operator(Parameters::Identifier(s), Parameters::Null, ram, minus)
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, minus);
-            match v {
-                Parameters::Int(i) => Parameters::Int(-i),
-                _ => Parameters::Null,
-            }
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, minus)
         }
         (Parameters::Identifier(s), Parameters::Float(i)) => {
             apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus)
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, minus);
-            match v {
-                Parameters::Float(i) => Parameters::Float(-i),
-                _ => Parameters::Null,
-            }
+            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, minus)
         }
 
         (Parameters::InterpreterVector(vec), Parameters::Identifier(s)) => apply_operator_reverse(
@@ -284,9 +314,7 @@ pub fn minus(
         (Parameters::Identifier(s), Bool(b)) => {
             apply_operator(Parameters::Identifier(s), Bool(b), ram, minus)
         }
-        _ => Parameters::Identifier(
-            "Those two values are incompatible with the - operator".to_string(),
-        ),
+        _ => incompatible_operator("-"),
     }
 }
 
@@ -295,12 +323,32 @@ pub fn mult(
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
 ) -> Parameters {
+    if let Some((a, b)) = symbolic_operands(&i, &i2, ram) {
+        return a.mult(&b).map_or_else(|e| e, Expression::into_parameters);
+    }
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return complex_result(c * c2);
+    }
     match (i, i2) {
+        (Parameters::Complex(c), Parameters::Null) | (Parameters::Null, Parameters::Complex(c)) => {
+            Parameters::Complex(c)
+        }
+        (Parameters::InterpreterVector(vec), Parameters::Complex(c))
+        | (Parameters::Complex(c), Parameters::InterpreterVector(vec)) => {
+            let mut result = Vec::new();
+            vec.into_iter()
+                .map(|x| mult(x.clone(), Parameters::Complex(c), ram))
+                .for_each(|x| result.push(x));
+            Parameters::InterpreterVector(Box::from(result))
+        }
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Int(v * v2),
+        (Parameters::Int(v), Parameters::Int(v2)) => match v.checked_mul(v2) {
+            Some(r) => Parameters::Int(r),
+            None => Parameters::from_big_int(BigInt::from(v) * v2),
+        },
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64) * f),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v * f),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v * (i1 as f64)),
@@ -382,6 +430,8 @@ pub fn mult(
                 match sum {
                     Parameters::Int(i) => Parameters::Int(i),
                     Parameters::Float(f) => Parameters::Float(f),
+                    Parameters::Rational(r) => Parameters::Rational(r),
+                    Parameters::Complex(c) => Parameters::Complex(c),
                     _ => Parameters::Float(f64::NAN),
                 }
             } else {
@@ -463,9 +513,7 @@ pub fn mult(
         (Parameters::Identifier(s), Bool(b)) => {
             apply_operator(Parameters::Identifier(s), Bool(b), ram, mult)
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the * operator".to_string(),
-        ),
+        _ => incompatible_operator("*"),
     }
 }
 
@@ -474,7 +522,25 @@ pub fn divide(
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
 ) -> Parameters {
+    if let Some((a, b)) = symbolic_operands(&i, &i2, ram) {
+        return a.divide(&b).map_or_else(|e| e, Expression::into_parameters);
+    }
+    let zero = match &i2 {
+        Parameters::Int(0) => true,
+        Parameters::Float(f) => *f == 0.0,
+        Parameters::Rational(s) => s.clone().is_null(),
+        _ => false,
+    };
+    if zero && i != Parameters::Null {
+        return error(ErrorKind::DivisionByZero, "Division by zero".to_string());
+    }
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return complex_result(c / c2);
+    }
     match (i, i2) {
+        (Parameters::Complex(c), Parameters::Null) | (Parameters::Null, Parameters::Complex(c)) => {
+            Parameters::Complex(c)
+        }
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
@@ -537,11 +603,7 @@ pub fn divide(
             apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide)
         }
         (Parameters::Int(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Int(i), ram, divide);
-            match v {
-                Parameters::Float(i) => Parameters::Float(1.0 / i),
-                _ => Parameters::Null,
-            }
+            apply_operator_reverse(Parameters::Int(i), Parameters::Identifier(s), ram, divide)
         }
         (Parameters::Null, Parameters::Identifier(s)) => {
             apply_operator(Parameters::Identifier(s), Parameters::Null, ram, divide)
@@ -553,11 +615,7 @@ pub fn divide(
             apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide)
         }
         (Parameters::Float(i), Parameters::Identifier(s)) => {
-            let v = apply_operator(Parameters::Identifier(s), Parameters::Float(i), ram, divide);
-            match v {
-                Parameters::Float(i) => Parameters::Float(1.0 / i),
-                _ => Parameters::Null,
-            }
+            apply_operator_reverse(Parameters::Float(i), Parameters::Identifier(s), ram, divide)
         }
         (Bool(b), Parameters::Identifier(s)) => {
             apply_operator_reverse(Bool(b), Parameters::Identifier(s), ram, divide)
@@ -565,10 +623,35 @@ pub fn divide(
         (Parameters::Identifier(s), Bool(b)) => {
             apply_operator(Parameters::Identifier(s), Bool(b), ram, divide)
         }
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the / operator".to_string(),
-        ),
+        _ => incompatible_operator("/"),
+    }
+}
+
+fn complex_power(c: Complex64, c2: Complex64) -> Complex64 {
+    if c2.im == 0.0 && c2.re.fract() == 0.0 && c2.re.abs() <= i32::MAX as f64 {
+        c.powi(c2.re as i32)
+    } else {
+        c.powc(c2)
+    }
+}
+
+const MAX_EXACT_EXPONENT: u32 = 100_000;
+
+fn exact_power(base: Rationals, exponent: i64) -> Option<Parameters> {
+    let e = u32::try_from(exponent.unsigned_abs()).ok()?;
+    if e > MAX_EXACT_EXPONENT || (exponent < 0 && base.clone().is_null()) {
+        return None;
     }
+    let (over, under) = (base.over.pow(e), base.under.pow(e));
+    let r = if exponent < 0 {
+        Rationals::from_big(over, under).reduce()
+    } else {
+        Rationals::from_big(under, over).reduce()
+    };
+    Some(match r.to_int() {
+        Some(i) if base.under == BigInt::from(1) => Parameters::Int(i),
+        _ => Parameters::Rational(r),
+    })
 }
 
 pub fn expo(
@@ -576,12 +659,33 @@ pub fn expo(
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
 ) -> Parameters {
+    if let Some((a, b)) = symbolic_operands(&i, &i2, ram) {
+        return match b.into_parameters().to_rational().and_then(|r| r.to_int()) {
+            Some(n) => a.pow(n).map_or_else(|e| e, Expression::into_parameters),
+            None => error(
+                ErrorKind::IncompatibleTypes,
+                "Symbolic expressions can only be raised to an integer power".to_string(),
+            ),
+        };
+    }
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return complex_result(complex_power(c, c2));
+    }
+    if let (true, Some(c), Some(c2)) = (is_negative_real(&i), to_complex(&i), to_complex(&i2)) {
+        if c2.re.fract() != 0.0 {
+            return complex_result(c.powc(c2));
+        }
+    }
     match (i, i2) {
+        (Parameters::Complex(c), Parameters::Null) | (Parameters::Null, Parameters::Complex(c)) => {
+            Parameters::Complex(c)
+        }
         (Parameters::Null, Parameters::Int(v)) => Parameters::Int(v),
         (Parameters::Null, Parameters::Float(f)) => Parameters::Float(f),
         (Parameters::Int(v), Parameters::Null) => Parameters::Int(v),
         (Parameters::Float(f), Parameters::Null) => Parameters::Float(f),
-        (Parameters::Int(v), Parameters::Int(v2)) => Parameters::Float((v as f64).powf(v2 as f64)),
+        (Parameters::Int(v), Parameters::Int(v2)) => exact_power(Rationals::new(1, v), v2)
+            .unwrap_or(Parameters::Float((v as f64).powf(v2 as f64))),
         (Parameters::Int(v), Parameters::Float(f)) => Parameters::Float((v as f64).powf(f)),
         (Parameters::Float(v), Parameters::Float(f)) => Parameters::Float(v.powf(f)),
         (Parameters::Float(v), Parameters::Int(i1)) => Parameters::Float(v.powf(i1 as f64)),
@@ -592,7 +696,7 @@ pub fn expo(
             Parameters::Float(s.approx().powf(s2.approx()))
         }
         (Parameters::Rational(s), Parameters::Int(i)) => {
-            Parameters::Float(s.approx().powf(i as f64))
+            exact_power(s.clone(), i).unwrap_or(Parameters::Float(s.approx().powf(i as f64)))
         }
         (Parameters::Int(i), Parameters::Rational(s)) => {
             Parameters::Float((i as f64).powf(s.approx()))
@@ -650,8 +754,118 @@ pub fn expo(
             apply_operator(Parameters::Identifier(s), Bool(b), ram, expo)
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the ^ operator".to_string(),
+        _ => incompatible_operator("^"),
+    }
+}
+
+pub enum Index {
+    At(Parameters),
+    Slice(Parameters, Parameters),
+}
+
+fn index_value(p: &Parameters) -> Result<i64, Parameters> {
+    match p {
+        Parameters::Int(i) => Ok(*i),
+        Parameters::Rational(r) if r.under == BigInt::from(1) => r.over.to_i64().ok_or_else(|| {
+            error(
+                ErrorKind::OutOfBounds,
+                format!("The index {r} is too large"),
+            )
+        }),
+        _ => Err(error(
+            ErrorKind::IncompatibleTypes,
+            format!("The index {p} is not an integer"),
+        )),
+    }
+}
+
+fn position(p: &Parameters, len: usize) -> Result<usize, Parameters> {
+    let i = index_value(p)?;
+    let k = if i < 0 { i + len as i64 } else { i };
+    if k < 0 || k >= len as i64 {
+        return Err(error(
+            ErrorKind::OutOfBounds,
+            format!("The index {i} is out of bounds for a vector of length {len}"),
+        ));
+    }
+    Ok(k as usize)
+}
+
+fn bound(p: &Parameters, len: usize, default: usize) -> Result<usize, Parameters> {
+    match p {
+        Parameters::Null => Ok(default),
+        p => {
+            let i = index_value(p)?;
+            let k = if i < 0 { i + len as i64 } else { i };
+            Ok(k.clamp(0, len as i64) as usize)
+        }
+    }
+}
+
+fn not_a_vector(p: &Parameters) -> Parameters {
+    match p {
+        Parameters::Identifier(s) => unknown_variable(s),
+        _ => error(
+            ErrorKind::IncompatibleTypes,
+            "Only vectors and matrices can be indexed".to_string(),
+        ),
+    }
+}
+
+pub fn get_index(value: Parameters, indices: &[Index]) -> Parameters {
+    let (first, rest) = match indices.split_first() {
+        None => return value,
+        Some(t) => t,
+    };
+    let vec = match value {
+        Parameters::InterpreterVector(v) => v,
+        p => return not_a_vector(&p),
+    };
+    match first {
+        Index::At(i) => match position(i, vec.len()) {
+            Ok(k) => get_index(vec[k].clone(), rest),
+            Err(e) => e,
+        },
+        Index::Slice(from, to) => {
+            let (lo, hi) = match (bound(from, vec.len(), 0), bound(to, vec.len(), vec.len())) {
+                (Ok(lo), Ok(hi)) => (lo, hi.max(lo)),
+                (Err(e), _) | (_, Err(e)) => return e,
+            };
+            let res: Vec<Parameters> = vec[lo..hi]
+                .iter()
+                .map(|x| get_index(x.clone(), rest))
+                .collect();
+            match res.iter().find(|x| matches!(x, Parameters::Error(_))) {
+                Some(e) => e.clone(),
+                None => Parameters::InterpreterVector(Box::from(res)),
+            }
+        }
+    }
+}
+
+pub fn set_index(value: Parameters, indices: &[Index], new: Parameters) -> Parameters {
+    let (first, rest) = match indices.split_first() {
+        None => return new,
+        Some(t) => t,
+    };
+    let mut vec = match value {
+        Parameters::InterpreterVector(v) => v,
+        p => return not_a_vector(&p),
+    };
+    match first {
+        Index::At(i) => match position(i, vec.len()) {
+            Ok(k) => match set_index(vec[k].clone(), rest, new) {
+                e @ Parameters::Error(_) => e,
+                p => {
+                    vec[k] = p;
+                    Parameters::InterpreterVector(vec)
+                }
+            },
+            Err(e) => e,
+        },
+        Index::Slice(_, _) => error(
+            ErrorKind::IncompatibleTypes,
+            "Only single elements can be assigned".to_string(),
         ),
     }
 }
@@ -741,9 +955,7 @@ pub fn greater(
             apply_operator(Parameters::Identifier(s), Bool(b), ram, greater)
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the > operator".to_string(),
-        ),
+        _ => incompatible_operator(">"),
     }
 }
 
@@ -818,9 +1030,7 @@ pub fn lesser(
             apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser)
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the < operator".to_string(),
-        ),
+        _ => incompatible_operator("<"),
     }
 }
 
@@ -915,9 +1125,7 @@ pub fn greater_or_equal(
             apply_operator(Parameters::Identifier(s), Bool(b), ram, greater_or_equal)
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the >= operator".to_string(),
-        ),
+        _ => incompatible_operator(">="),
     }
 }
 
@@ -1012,9 +1220,7 @@ pub fn lesser_or_equal(
             apply_operator(Parameters::Identifier(s), Bool(b), ram, lesser_or_equal)
         }
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the <= operator".to_string(),
-        ),
+        _ => incompatible_operator("<="),
     }
 }
 
@@ -1023,6 +1229,9 @@ pub fn equal(
     i2: Parameters,
     ram: Option<&HashMap<String, Parameters>>,
 ) -> Parameters {
+    if let Some((c, c2)) = complex_operands(&i, &i2) {
+        return Bool(c == c2);
+    }
     match (i, i2) {
         (Parameters::Null, Parameters::Int(_)) => Bool(true),
         (Parameters::Null, Parameters::Float(_)) => Bool(true),
@@ -1091,9 +1300,7 @@ pub fn equal(
             equal,
         ),
 
-        _ => Parameters::Identifier(
-            "@Those two values are incompatible with the == operator".to_string(),
-        ),
+        _ => incompatible_operator("=="),
     }
 }
 
@@ -1155,7 +1362,10 @@ pub fn or(i: Parameters, i2: Parameters, ram: Option<&HashMap<String, Parameters
 
 #[cfg(test)]
 mod test {
-    use crate::interpreting::function::{add, divide, minus, mult};
+    use num_bigint::BigInt;
+
+    use crate::exact_math::rationals::Rationals;
+    use crate::interpreting::function::{add, divide, expo, minus, mult};
     use crate::parsing::ast::Parameters;
 
     #[test]
@@ -1279,8 +1489,7 @@ mod test {
 
     #[test]
     pub fn test_divide_simple() {
-        let expected =
-            Parameters::Rational(crate::exact_math::rationals::Rationals { under: 1, over: 1 });
+        let expected = Parameters::Rational(crate::exact_math::rationals::Rationals::new(1, 1));
         let result = divide(Parameters::Int(1), Parameters::Int(1), None);
         assert_eq!(result, expected);
     }
@@ -1305,4 +1514,26 @@ mod test {
         let result = divide(Parameters::Float(0.1), Parameters::Int(2), None);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn test_mult_overflow() {
+        let expected = Parameters::Rational(Rationals::from_big(
+            BigInt::from(1),
+            BigInt::from(i64::MAX) * 2,
+        ));
+        let result = mult(Parameters::Int(i64::MAX), Parameters::Int(2), None);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn test_expo_exact() {
+        assert_eq!(
+            expo(Parameters::Int(2), Parameters::Int(10), None),
+            Parameters::Int(1024)
+        );
+        assert_eq!(
+            expo(Parameters::Int(2), Parameters::Int(-2), None),
+            Parameters::Rational(Rationals::new(4, 1))
+        );
+    }
 }
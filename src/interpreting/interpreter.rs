@@ -1,12 +1,25 @@
 use std::collections::HashMap;
 
+use crate::exact_math::algebra::free_variable;
 use crate::exact_math::rationals::Rationals;
+use crate::interpreting::error::{error, unknown_variable, CalcError, ErrorKind};
 use crate::interpreting::function::{
-    add, and, assign, divide, equal, expo, greater, greater_or_equal, lesser, lesser_or_equal,
-    minus, mult, not, or,
+    add, and, assign, divide, equal, expo, get_index, greater, greater_or_equal, lesser,
+    lesser_or_equal, minus, mult, not, or, set_index, Index,
 };
-use crate::interpreting::stdlib::exec;
-use crate::parsing::ast::{Ast, Parameters};
+use crate::interpreting::stdlib::{exec, exec_lazy};
+use crate::parsing::ast::{expression_source, Ast, Parameters};
+
+fn located(mut e: CalcError, ast: &Ast) -> Parameters {
+    let position = ast.position();
+    if let (None, Some(p)) = (e.position, position.column) {
+        e = e.with_position(p);
+    }
+    match (&e.expression, expression_source(ast)) {
+        (None, Some(s)) => Parameters::Error(e.with_expression(s).with_span(position.span)),
+        _ => Parameters::Error(e),
+    }
+}
 
 pub fn interpret(
     ast: &Ast,
@@ -19,9 +32,34 @@ pub fn interpret(
             value: v,
             left: l,
             right: r,
+            ..
         } => {
-            let param1 = interpret(l, &mut ram, &mut function);
-            let param2 = interpret(r, &mut ram, &mut function);
+            if let (Parameters::Assign, Ast::Index { .. }) = (v, l.as_ref()) {
+                return match assign_index(l, r, ram, function) {
+                    Parameters::Error(e) => located(e, ast),
+                    p => p,
+                };
+            }
+            let (param1, param2) = match (v, l.as_ref()) {
+                (Parameters::Assign, Ast::Call { .. }) => (Parameters::Null, Parameters::Null),
+                (
+                    Parameters::Assign,
+                    Ast::Node {
+                        value: Parameters::Identifier(s),
+                        ..
+                    },
+                ) => (
+                    Parameters::Identifier(s.clone()),
+                    free_variable(interpret(r, ram, function)),
+                ),
+                _ => (
+                    interpret(l, &mut ram, &mut function),
+                    interpret(r, &mut ram, &mut function),
+                ),
+            };
+            if let Some(e) = param1.first_error().or(param2.first_error()) {
+                return located(e, ast);
+            }
             let last = match v {
                 Parameters::PlusOperation => add(param1, param2, Some(&ram)),
                 Parameters::MinusOperation => minus(param1, param2, Some(&ram)),
@@ -39,7 +77,9 @@ pub fn interpret(
                 Parameters::Rational(s) => Parameters::Rational(s.clone()),
                 Parameters::Str(s) => Parameters::Str(s.to_string()),
                 Parameters::Assign => match *(l.clone()) {
-                    Ast::Call { name: n, lst: list } => {
+                    Ast::Call {
+                        name: n, lst: list, ..
+                    } => {
                         if function.contains_key(&n) {
                             Parameters::Str("This function has already been set".to_string())
                         } else {
@@ -71,6 +111,7 @@ pub fn interpret(
                 },
                 Parameters::Float(f) => Parameters::Rational(Rationals::rationalize(*f)),
                 Parameters::Int(i) => Parameters::Int(*i),
+                Parameters::Complex(c) => Parameters::Complex(*c),
                 Parameters::Identifier(s) => {
                     if ram.contains_key(s) {
                         ram.get(s).unwrap().clone()
@@ -89,23 +130,359 @@ pub fn interpret(
                     Parameters::InterpreterVector(Box::from(vec))
                 }
                 Parameters::InterpreterVector(a) => Parameters::InterpreterVector(a.clone()),
+                Parameters::SliceOperation => error(
+                    ErrorKind::Syntax,
+                    "Slices can only be used inside brackets".to_string(),
+                ),
+                Parameters::Lambda(params, body) => {
+                    Parameters::Lambda(params.clone(), Box::from(capture(body, params, ram)))
+                }
+                Parameters::Symbolic(e) => Parameters::Symbolic(e.clone()),
+                Parameters::Error(e) => return Parameters::Error(e.clone()),
             };
-            last.clone()
+            match last.first_error() {
+                Some(e) => located(e, ast),
+                None => last,
+            }
         }
-        Ast::Call { name: n, lst: list } => {
+        Ast::Call {
+            name: n, lst: list, ..
+        } => {
+            if let Some(p) = exec_lazy(n, list, ram, function) {
+                return match p {
+                    Parameters::Error(e) => located(e, ast),
+                    p => p,
+                };
+            }
             let v: Vec<Parameters> = list.iter().map(|x| interpret(x, ram, function)).collect();
-            exec(n.to_string(), v, Some(&mut ram), Some(&mut function))
+            if let Some(e) = v.iter().find_map(|x| x.first_error()) {
+                return located(e, ast);
+            }
+            match exec(n.to_string(), v, Some(&mut ram), Some(&mut function)) {
+                Parameters::Error(e) => located(e, ast),
+                p => p,
+            }
+        }
+        Ast::Index {
+            target: t,
+            indices: i,
+            ..
+        } => {
+            let value = interpret(t, ram, function);
+            let result = match interpret_indices(i, ram, function) {
+                _ if value.first_error().is_some() => value,
+                Ok(indices) => get_index(value, &indices),
+                Err(e) => e,
+            };
+            match result.first_error() {
+                Some(e) => located(e, ast),
+                None => result,
+            }
+        }
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            ..
+        } => match interpret(c, &mut ram, &mut function) {
+            Parameters::Bool(true) => interpret(t, &mut ram, &mut function),
+            Parameters::Bool(false) => interpret(o, &mut ram, &mut function),
+            Parameters::Error(e) => located(e, ast),
+            _ => located(
+                CalcError::new(
+                    ErrorKind::NotABoolean,
+                    "The condition must be a boolean".to_string(),
+                ),
+                c,
+            ),
+        },
+    }
+}
+
+fn interpret_indices(
+    indices: &Vec<Ast>,
+    ram: &mut HashMap<String, Parameters>,
+    function: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Result<Vec<Index>, Parameters> {
+    let mut res = Vec::new();
+    for index in indices {
+        let i = match index {
+            Ast::Node {
+                value: Parameters::SliceOperation,
+                left: l,
+                right: r,
+                ..
+            } => Index::Slice(interpret(l, ram, function), interpret(r, ram, function)),
+            _ => Index::At(interpret(index, ram, function)),
+        };
+        match &i {
+            Index::At(p) | Index::Slice(p, _) | Index::Slice(_, p) if p.first_error().is_some() => {
+                return Err(p.clone())
+            }
+            _ => res.push(i),
+        }
+    }
+    Ok(res)
+}
+
+fn assign_index(
+    left: &Ast,
+    right: &Ast,
+    ram: &mut HashMap<String, Parameters>,
+    function: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    let mut target = left;
+    let mut chain = Vec::new();
+    while let Ast::Index {
+        target: t,
+        indices: i,
+        ..
+    } = target
+    {
+        chain.push(i);
+        target = t;
+    }
+    let name = match target {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            ..
+        } => s.to_string(),
+        _ => {
+            return error(
+                ErrorKind::IncompatibleTypes,
+                "Only elements of a variable can be assigned".to_string(),
+            )
+        }
+    };
+    let mut indices = Vec::new();
+    for i in chain.into_iter().rev() {
+        match interpret_indices(i, ram, function) {
+            Ok(mut v) => indices.append(&mut v),
+            Err(e) => return e,
+        }
+    }
+    let value = interpret(right, ram, function);
+    if value.first_error().is_some() {
+        return value;
+    }
+    let current = match ram.get(&name) {
+        None => return unknown_variable(&name),
+        Some(v) => v.clone(),
+    };
+    match set_index(current, &indices, value) {
+        e @ Parameters::Error(_) => e,
+        p => {
+            ram.insert(name.clone(), p.clone());
+            Parameters::Identifier(format!(
+                "@ {} = {}",
+                name,
+                p.pretty_print(Some(ram), Some(function))
+            ))
+        }
+    }
+}
+
+pub(crate) fn capture(ast: &Ast, params: &Vec<String>, ram: &HashMap<String, Parameters>) -> Ast {
+    match ast {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil && !params.contains(s) => match ram.get(s) {
+            Some(Parameters::Symbolic(e)) => e.to_ast(),
+            Some(v) => Ast::new(v.clone()),
+            None => ast.clone(),
+        },
+        Ast::Node {
+            value: Parameters::Lambda(p, body),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => {
+            let mut inner = params.clone();
+            inner.extend(p.iter().cloned());
+            Ast::new(Parameters::Lambda(
+                p.clone(),
+                Box::from(capture(body, &inner, ram)),
+            ))
         }
+        Ast::Node {
+            value: Parameters::Vector(v),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => Ast::new(Parameters::Vector(Box::from(
+            v.iter()
+                .map(|x| capture(x, params, ram))
+                .collect::<Vec<Ast>>(),
+        ))),
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            position: p,
+        } => Ast::Node {
+            value: v.clone(),
+            left: Box::from(capture(l, params, ram)),
+            right: Box::from(capture(r, params, ram)),
+            position: *p,
+        },
+        Ast::Call {
+            name: n,
+            lst: l,
+            position: p,
+        } => Ast::Call {
+            name: n.clone(),
+            lst: l.iter().map(|x| capture(x, params, ram)).collect(),
+            position: *p,
+        },
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            position: p,
+        } => Ast::If {
+            condition: Box::from(capture(c, params, ram)),
+            then: Box::from(capture(t, params, ram)),
+            otherwise: Box::from(capture(o, params, ram)),
+            position: *p,
+        },
+        Ast::Index {
+            target: t,
+            indices: i,
+            position: p,
+        } => Ast::Index {
+            target: Box::from(capture(t, params, ram)),
+            indices: i.iter().map(|x| capture(x, params, ram)).collect(),
+            position: *p,
+        },
+        Ast::Nil => Ast::Nil,
     }
 }
 
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
+    use std::str::FromStr;
 
+    use num_bigint::BigInt;
+    use num_complex::Complex64;
+
+    use crate::exact_math::algebra::{Expression, Factors, Polynomial};
     use crate::exact_math::rationals::Rationals;
+    use crate::interpreting::error::ErrorKind;
     use crate::interpreting::interpreter::interpret;
-    use crate::parsing::ast::{Ast, Parameters};
+    use crate::interpreting::stdlib::set_stack_size;
+    use crate::lexing::lexer::lex_with_positions;
+    use crate::lexing::token::Token;
+    use crate::parsing::ast::{Ast, Parameters, Position};
+    use crate::parsing::parser::init_calc_parser;
+
+    fn parse(line: &str) -> Ast {
+        let (lexed, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions(line.to_string()).into_iter().unzip();
+        init_calc_parser(&lexed).with_positions(positions).parse()
+    }
+
+    fn run(
+        line: &str,
+        ram: &mut HashMap<String, Parameters>,
+        function: &mut HashMap<String, (Vec<Ast>, Ast)>,
+    ) -> Parameters {
+        interpret(&parse(line), ram, function)
+    }
+
+    fn eval(line: &str) -> Parameters {
+        run(line, &mut HashMap::new(), &mut HashMap::new())
+    }
+
+    fn vector(v: Vec<i64>) -> Parameters {
+        Parameters::InterpreterVector(Box::from(
+            v.into_iter()
+                .map(Parameters::Int)
+                .collect::<Vec<Parameters>>(),
+        ))
+    }
+
+    fn fractions(v: Vec<(i64, i64)>) -> Parameters {
+        Parameters::InterpreterVector(Box::from(
+            v.into_iter()
+                .map(|(over, under)| Parameters::Rational(Rationals::new(under, over)))
+                .collect::<Vec<Parameters>>(),
+        ))
+    }
+
+    fn list(v: Vec<Parameters>) -> Parameters {
+        Parameters::InterpreterVector(Box::from(v))
+    }
+
+    fn rational(over: i64, under: i64) -> Parameters {
+        Parameters::Rational(Rationals::new(under, over))
+    }
+
+    fn assert_error(p: Parameters, kind: ErrorKind) {
+        match p {
+            Parameters::Error(e) => assert_eq!(e.kind, kind),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    fn assert_close(p: Parameters, expected: f64) {
+        match p.to_f64() {
+            Some(f) => assert!((f - expected).abs() < 1e-9, "{f} != {expected}"),
+            None => panic!("expected a number, got {p}"),
+        }
+    }
+
+    fn assert_close_matrix(p: Parameters, expected: Vec<Vec<f64>>) {
+        match p {
+            Parameters::InterpreterVector(rows) if rows.len() == expected.len() => {
+                for (row, expected) in rows.iter().zip(expected) {
+                    match row {
+                        Parameters::InterpreterVector(r) if r.len() == expected.len() => r
+                            .iter()
+                            .zip(expected)
+                            .for_each(|(x, y)| assert_close(x.clone(), y)),
+                        r => panic!("expected a row of {} numbers, got {r}", expected.len()),
+                    }
+                }
+            }
+            p => panic!("expected a matrix with {} rows, got {p}", expected.len()),
+        }
+    }
+
+    fn polynomial(line: &str) -> Polynomial {
+        match eval(line) {
+            Parameters::Symbolic(e) => e.num,
+            Parameters::Identifier(s) => Polynomial::variable(&s),
+            p => panic!("expected a polynomial, got {p}"),
+        }
+    }
+
+    fn factors(p: Parameters) -> Factors {
+        match p {
+            Parameters::Symbolic(e) => match e.factors {
+                Some(f) => f,
+                None => panic!("expected a factored expression, got {e}"),
+            },
+            p => panic!("expected an expression, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_big_int() {
+        let big = |digits: &str| Parameters::from_big_int(BigInt::from_str(digits).unwrap());
+        assert_eq!(
+            eval("99999999999999999999999"),
+            big("99999999999999999999999")
+        );
+        assert_eq!(
+            eval("99999999999999999999999 + 1"),
+            big("100000000000000000000000")
+        );
+        assert_eq!(eval("618970019642690137449562111"), eval("2^89 - 1"));
+    }
 
     #[test]
     fn test_interpreter_int() {
@@ -116,6 +493,7 @@ mod test {
             value: Parameters::Int(2),
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
@@ -130,6 +508,7 @@ mod test {
             value: Parameters::Float(2.0),
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
@@ -144,6 +523,7 @@ mod test {
             value: Parameters::PlusOperation,
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
@@ -158,6 +538,7 @@ mod test {
             value: Parameters::MinusOperation,
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
@@ -172,6 +553,7 @@ mod test {
             value: Parameters::MultiplicationOperation,
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
@@ -181,14 +563,1157 @@ mod test {
     fn test_interpreter_divide_operation() {
         let mut ram: HashMap<String, Parameters> = HashMap::new();
         let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-        let expected =
-            Parameters::Rational(crate::exact_math::rationals::Rationals { under: 1, over: 1 });
+        let expected = Parameters::Rational(crate::exact_math::rationals::Rationals::new(1, 1));
         let ast = Ast::Node {
             value: Parameters::DivideOperation,
             left: Box::from(Ast::new(Parameters::Int(1))),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = interpret(&ast, &mut ram, &mut function);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn test_interpreter_if() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let result = run("if 1 > 2 then 1 else 2", &mut ram, &mut function);
+        assert_eq!(result, Parameters::Int(2))
+    }
+
+    #[test]
+    fn test_interpreter_piecewise_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("abs2(x) = if x < 0 then -x else x", &mut ram, &mut function);
+        run(
+            "sign(x) = x > 0 ? 1 : x < 0 ? -1 : 0",
+            &mut ram,
+            &mut function,
+        );
+        assert_eq!(run("abs2(-3)", &mut ram, &mut function), Parameters::Int(3));
+        assert_eq!(run("abs2(4)", &mut ram, &mut function), Parameters::Int(4));
+        assert_eq!(
+            run("sign(-3)", &mut ram, &mut function),
+            Parameters::Int(-1)
+        );
+        assert_eq!(run("sign(0)", &mut ram, &mut function), Parameters::Int(0));
+    }
+
+    #[test]
+    fn test_interpreter_recursive_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run(
+            "f(n) = if n <= 1 then 1 else n * f(n-1)",
+            &mut ram,
+            &mut function,
+        );
+        let result = run("f(10)", &mut ram, &mut function);
+        assert_eq!(result, Parameters::Int(3628800))
+    }
+
+    #[test]
+    fn test_interpreter_mutual_recursion() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run(
+            "even(n) = if n == 0 then true else odd(n-1)",
+            &mut ram,
+            &mut function,
+        );
+        run(
+            "odd(n) = if n == 0 then false else even(n-1)",
+            &mut ram,
+            &mut function,
+        );
+        let result = run("even(10)", &mut ram, &mut function);
+        assert_eq!(result, Parameters::Bool(true))
+    }
+
+    #[test]
+    fn test_interpreter_recursion_limit() {
+        let child = std::thread::Builder::new()
+            .stack_size(64 * 1024 * 1024)
+            .spawn(|| {
+                let mut ram: HashMap<String, Parameters> = HashMap::new();
+                let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+                run("loop(n) = loop(n+1)", &mut ram, &mut function);
+                run("loop(0)", &mut ram, &mut function)
+            })
+            .unwrap();
+        match child.join().unwrap() {
+            Parameters::Error(e) => {
+                assert_eq!(e.kind, ErrorKind::RecursionLimit);
+                assert_eq!(e.message, "Maximum recursion depth of 1000 exceeded")
+            }
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_recursion_stack_guard() {
+        let child = std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                set_stack_size(4 * 1024 * 1024);
+                let mut ram: HashMap<String, Parameters> = HashMap::new();
+                let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+                run("loop(n) = loop(n+1)", &mut ram, &mut function);
+                run("loop(0)", &mut ram, &mut function)
+            })
+            .unwrap();
+        match child.join().unwrap() {
+            Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::RecursionLimit),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_unknown_variable() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        match run("x > 1", &mut ram, &mut function) {
+            Parameters::Error(e) => {
+                assert_eq!(e.kind, ErrorKind::UnknownVariable);
+                assert_eq!(e.expression, Some("x > 1".to_string()))
+            }
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_division_by_zero() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        match run("2 * (1 / 0) + 3", &mut ram, &mut function) {
+            Parameters::Error(e) => {
+                assert_eq!(e.kind, ErrorKind::DivisionByZero);
+                assert_eq!(e.expression, Some("1 / 0".to_string()));
+                assert_eq!(e.position, Some(7))
+            }
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_error_expression_is_source() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        match run("fact(-1)", &mut ram, &mut function) {
+            Parameters::Error(e) => assert_eq!(e.expression, Some("fact(-1)".to_string())),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_error_is_not_assigned() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("y = foo(1)", &mut ram, &mut function);
+        assert_eq!(ram.contains_key("y"), false)
+    }
+
+    #[test]
+    fn test_interpreter_complex() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        ram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
+        assert_eq!(
+            run("(1+2*i)*(3-i)", &mut ram, &mut function),
+            Parameters::Complex(Complex64::new(5.0, 5.0))
+        );
+        assert_eq!(
+            run("sqrt(-4)", &mut ram, &mut function),
+            Parameters::Complex(Complex64::new(0.0, 2.0))
+        );
+        assert_eq!(run("i*i", &mut ram, &mut function), Parameters::Float(-1.0));
+    }
+
+    #[test]
+    fn test_interpreter_negative_base_power() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        for (line, re, im) in [
+            ("(-1)^(1/2)", 0.0, 1.0),
+            ("(-8)^(1/3)", 1.0, 3f64.sqrt()),
+            ("(-4)^0.5", 0.0, 2.0),
+        ] {
+            match run(line, &mut ram, &mut function) {
+                Parameters::Complex(c) => {
+                    assert_close(Parameters::Float(c.re), re);
+                    assert_close(Parameters::Float(c.im), im);
+                }
+                p => panic!("{line} should be complex, got {p}"),
+            }
+        }
+        assert_eq!(run("(-2)^3", &mut ram, &mut function), Parameters::Int(-8));
+    }
+
+    #[test]
+    fn test_interpreter_reassign_constant() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        ram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
+        run("i = 1", &mut ram, &mut function);
+        run("v = [4,5,6]", &mut ram, &mut function);
+        assert_eq!(run("v[i]", &mut ram, &mut function), Parameters::Int(5));
+        run("i = 2", &mut ram, &mut function);
+        assert_eq!(run("i", &mut ram, &mut function), Parameters::Int(2));
+    }
+
+    #[test]
+    fn test_interpreter_lambda() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("k = 3", &mut ram, &mut function);
+        run("f = x -> k * x", &mut ram, &mut function);
+        run("k = 10", &mut ram, &mut function);
+        run("add(a,b) = a + b", &mut ram, &mut function);
+        assert_eq!(run("f(2)", &mut ram, &mut function), Parameters::Int(6));
+        assert_eq!(
+            run("map(f, [1,2,3])", &mut ram, &mut function),
+            vector(vec![3, 6, 9])
+        );
+        assert_eq!(
+            run("filter(x -> x > 1, [1,2,3])", &mut ram, &mut function),
+            vector(vec![2, 3])
+        );
+        assert_eq!(
+            run("reduce(add, [1,2,3,4])", &mut ram, &mut function),
+            Parameters::Int(10)
+        );
+        assert_eq!(
+            run(
+                "fold((acc,x) -> acc * x, 2, [3,4])",
+                &mut ram,
+                &mut function
+            ),
+            Parameters::Int(24)
+        );
+        assert_eq!(
+            run("zip([1,2],[3,4])", &mut ram, &mut function),
+            Parameters::InterpreterVector(Box::from(vec![vector(vec![1, 3]), vector(vec![2, 4])]))
+        );
+        match run("reduce(add, [])", &mut ram, &mut function) {
+            Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::WrongArguments),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_lambda_arity() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f = x -> x", &mut ram, &mut function);
+        run("g(x, y) = x + y", &mut ram, &mut function);
+        for call in ["f(1,2)", "f()", "g(1)", "map(g, [1,2])"] {
+            match run(call, &mut ram, &mut function) {
+                Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::WrongArguments),
+                p => panic!("expected an error for {call}, got {p}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpreter_anonymous_call() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        assert_eq!(
+            run("(x -> x + 1)(3)", &mut ram, &mut function),
+            Parameters::Int(4)
+        );
+        assert_eq!(
+            run("((x, y) -> x * y)(3, 4)", &mut ram, &mut function),
+            Parameters::Int(12)
+        );
+        run("k = x -> y -> x + y", &mut ram, &mut function);
+        assert_eq!(run("k(1)(2)", &mut ram, &mut function), Parameters::Int(3));
+    }
+
+    #[test]
+    fn test_interpreter_index() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("v = [1,2,3,4,5]", &mut ram, &mut function);
+        run("m = [[1,2,3],[4,5,6]]", &mut ram, &mut function);
+        assert_eq!(run("v[-1]", &mut ram, &mut function), Parameters::Int(5));
+        assert_eq!(run("v[1:3]", &mut ram, &mut function), vector(vec![2, 3]));
+        assert_eq!(run("v[3:]", &mut ram, &mut function), vector(vec![4, 5]));
+        assert_eq!(run("m[1][2]", &mut ram, &mut function), Parameters::Int(6));
+        assert_eq!(run("m[1,2]", &mut ram, &mut function), Parameters::Int(6));
+        assert_eq!(run("m[:,0]", &mut ram, &mut function), vector(vec![1, 4]));
+        run("m[0,1] = 9", &mut ram, &mut function);
+        assert_eq!(run("m[0]", &mut ram, &mut function), vector(vec![1, 9, 3]));
+        match run("v[5]", &mut ram, &mut function) {
+            Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::OutOfBounds),
+            p => panic!("expected an error, got {p}"),
+        }
+        match run("v[-6] = 1", &mut ram, &mut function) {
+            Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::OutOfBounds),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_range() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        let quarter = |n: i64| Parameters::Rational(Rationals::new(4, n).reduce());
+        assert_eq!(run("1..3", &mut ram, &mut function), vector(vec![1, 2, 3]));
+        assert_eq!(run("3..1", &mut ram, &mut function), vector(vec![3, 2, 1]));
+        assert_eq!(
+            run("0..1 step 1/4", &mut ram, &mut function),
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Int(0),
+                quarter(1),
+                quarter(2),
+                quarter(3),
+                Parameters::Int(1)
+            ]))
+        );
+        assert_eq!(
+            run("linspace(0,1,5)", &mut ram, &mut function),
+            run("range(0,1,1/4)", &mut ram, &mut function)
+        );
+        assert_eq!(run("1..2 step 3", &mut ram, &mut function), vector(vec![1]));
+        match run("1..2 step 0", &mut ram, &mut function) {
+            Parameters::Error(e) => assert_eq!(e.kind, ErrorKind::WrongArguments),
+            p => panic!("expected an error, got {p}"),
+        }
+    }
+
+    #[test]
+    fn test_interpreter_negative_range() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        assert_eq!(
+            run("-1..2", &mut ram, &mut function),
+            vector(vec![-1, 0, 1, 2])
+        );
+        assert_eq!(
+            run("-3..-1", &mut ram, &mut function),
+            vector(vec![-3, -2, -1])
+        );
+        assert_eq!(
+            run("-2..2 step 1", &mut ram, &mut function),
+            vector(vec![-2, -1, 0, 1, 2])
+        );
+        assert_eq!(
+            run("5..1 step -2", &mut ram, &mut function),
+            vector(vec![5, 3, 1])
+        );
+        assert_eq!(
+            run("-1..-7 step -3", &mut ram, &mut function),
+            vector(vec![-1, -4, -7])
+        );
+        assert_eq!(
+            run("-(1..3)", &mut ram, &mut function),
+            vector(vec![-1, -2, -3])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_series() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("k = 100", &mut ram, &mut function);
+        assert_eq!(
+            run("sum(k^2, k, 1, 10)", &mut ram, &mut function),
+            Parameters::Int(385)
+        );
+        assert_eq!(
+            run("sum(1/k, k, 1, 4)", &mut ram, &mut function),
+            Parameters::Rational(Rationals::new(12, 25))
+        );
+        assert_eq!(
+            run("prod(k, k, 1, 5)", &mut ram, &mut function),
+            Parameters::Int(120)
+        );
+        assert_eq!(
+            run("sum(k, k, [1, 2, 4])", &mut ram, &mut function),
+            Parameters::Int(7)
+        );
+        assert_eq!(
+            run("prod(k, k, 1, 0)", &mut ram, &mut function),
+            Parameters::Int(1)
+        );
+        assert_eq!(run("k", &mut ram, &mut function), Parameters::Int(100));
+    }
+
+    #[test]
+    fn test_interpreter_diff() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x^3 + 2*x", &mut ram, &mut function);
+        run("g = diff(f)", &mut ram, &mut function);
+        assert_eq!(run("g(2)", &mut ram, &mut function), Parameters::Int(14));
+        assert_eq!(
+            run("diff(f, 2)", &mut ram, &mut function).to_string(),
+            "x -> 6*x"
+        );
+        assert_eq!(
+            run("diff(x^2 + 1/x, x)", &mut ram, &mut function).to_string(),
+            "x -> 2*x - 1/x^2"
+        );
+        assert_eq!(
+            run("diff(exp(3*x), x)", &mut ram, &mut function).to_string(),
+            "x -> 3*exp(3*x)"
+        );
+        run("h = diff((x,y) -> x*y^2, y)", &mut ram, &mut function);
+        assert_eq!(run("h(3, 2)", &mut ram, &mut function), Parameters::Int(12));
+    }
+
+    #[test]
+    fn test_interpreter_diff_user_call() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x^2", &mut ram, &mut function);
+        run("k(x) = 3*x", &mut ram, &mut function);
+        run("df = diff(f(x), x)", &mut ram, &mut function);
+        run("dk = diff(k(x), x)", &mut ram, &mut function);
+        assert_eq!(run("df(5)", &mut ram, &mut function), Parameters::Int(10));
+        assert_eq!(run("dk(5)", &mut ram, &mut function), Parameters::Int(3));
+    }
+
+    #[test]
+    fn test_interpreter_diff_chain_rule() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("k(x) = 3*x", &mut ram, &mut function);
+        run("d = diff(k(x)^2, x)", &mut ram, &mut function);
+        assert_eq!(run("d(2)", &mut ram, &mut function), Parameters::Int(36));
+        run("e = diff(sin(k(x)), x)", &mut ram, &mut function);
+        assert_close(run("e(1)", &mut ram, &mut function), 3.0 * 3.0_f64.cos());
+    }
+
+    #[test]
+    fn test_interpreter_diff_function_value() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("d = diff(poly([1,2,3]), x)", &mut ram, &mut function);
+        assert_eq!(run("d(1)", &mut ram, &mut function), Parameters::Int(8));
+    }
+
+    #[test]
+    fn test_interpreter_diff_not_differentiable() {
+        assert_error(eval("diff(max(x, 1), x)"), ErrorKind::IncompatibleTypes);
+        assert_error(eval("diff(unknown(x), x)"), ErrorKind::IncompatibleTypes);
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_cancellation() {
+        assert_eq!(eval("(x+1)^2 - x^2"), eval("2*x + 1"));
+        assert_eq!(eval("(x+1) - x"), Parameters::Int(1));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_power() {
+        assert_eq!(eval("(x+y)^2"), eval("x^2 + 2*x*y + y^2"));
+        assert_eq!(eval("(x-1)^-1"), eval("1/(x - 1)"));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_fraction() {
+        assert_eq!(eval("(x^2-1)/(x-1)"), eval("x + 1"));
+        assert_eq!(eval("1/x + 1/y"), eval("(x + y)/(x*y)"));
+        assert_eq!(eval("(2*x+2)/(4*x^2-4)"), eval("1/(2*x - 2)"));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_factor() {
+        assert_eq!(
+            factors(eval("factor(x^3 - x)")),
+            Factors {
+                constant: Parameters::Int(1),
+                num: vec![
+                    (polynomial("x"), 1),
+                    (polynomial("x + 1"), 1),
+                    (polynomial("x - 1"), 1)
+                ],
+                den: vec![],
+            }
+        );
+        assert_eq!(
+            factors(eval("factor(6*x^2 + 5*x + 1)")),
+            Factors {
+                constant: Parameters::Int(1),
+                num: vec![(polynomial("2*x + 1"), 1), (polynomial("3*x + 1"), 1)],
+                den: vec![],
+            }
+        );
+        assert_eq!(
+            factors(eval("factor((x^2-1)/(2*x+4))")),
+            Factors {
+                constant: Parameters::Rational(Rationals::new(2, 1)),
+                num: vec![(polynomial("x + 1"), 1), (polynomial("x - 1"), 1)],
+                den: vec![(polynomial("x + 2"), 1)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_expand() {
+        assert_eq!(eval("expand((x+1)*(x-2))"), eval("x^2 - x - 2"));
+        assert_eq!(eval("expand(factor(x^2 - 1))"), eval("x^2 - 1"));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_simplify_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = (x+1)^2 - x^2", &mut ram, &mut function);
+        assert_eq!(
+            run("simplify(f)", &mut ram, &mut function),
+            Parameters::Lambda(vec!["x".to_string()], Box::from(parse("2*x + 1")))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_variable() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("p = (x+1)^2", &mut ram, &mut function);
+        assert_eq!(ram.get("p"), Some(&eval("x^2 + 2*x + 1")));
+        assert_eq!(run("p - 2*x", &mut ram, &mut function), eval("x^2 + 1"));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_argument() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x^2", &mut ram, &mut function);
+        run("g(x) = x", &mut ram, &mut function);
+        run("h = t -> t + 1", &mut ram, &mut function);
+        assert_eq!(run("f(x)", &mut ram, &mut function), eval("x^2"));
+        assert_eq!(
+            run("g(x)", &mut ram, &mut function),
+            Parameters::Symbolic(Box::new(Expression::variable("x")))
+        );
+        assert_eq!(run("h(t)", &mut ram, &mut function), eval("t + 1"));
+    }
+
+    #[test]
+    fn test_interpreter_assign_itself() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("y = y", &mut ram, &mut function);
+        assert_eq!(
+            run("y", &mut ram, &mut function),
+            Parameters::Symbolic(Box::new(Expression::variable("y")))
+        );
+        assert_eq!(run("y * 2", &mut ram, &mut function), eval("2*y"));
+        ram.insert("z".to_string(), Parameters::Identifier("z".to_string()));
+        assert_eq!(run("z + 1", &mut ram, &mut function), eval("z + 1"));
+        assert_eq!(run("1 + z", &mut ram, &mut function), eval("z + 1"));
+    }
+
+    #[test]
+    fn test_interpreter_symbolic_too_large() {
+        assert_error(eval("(x+1)^3000"), ErrorKind::Overflow);
+        assert_error(eval("(x+y+z+1)^20"), ErrorKind::Overflow);
+        assert_error(eval("x^3000000000 * x^3000000000"), ErrorKind::Overflow);
+        assert_error(eval("x^(2^40)"), ErrorKind::Overflow);
+        assert_error(eval("1/(x-x)"), ErrorKind::DivisionByZero);
+    }
+
+    #[test]
+    fn test_interpreter_integrate() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("pi = 3.141592653589793", &mut ram, &mut function);
+        run("f(x) = 3*x^2", &mut ram, &mut function);
+        for (input, expected) in [
+            ("integrate(cos, 0, pi/2)", 1.0),
+            ("integrate(f, 0, 2)", 8.0),
+            ("integrate(x -> 1/sqrt(x), 0, 1)", 2.0),
+            ("integrate(x^3, 2, 0)", -4.0),
+        ] {
+            match run(input, &mut ram, &mut function) {
+                Parameters::InterpreterVector(v) => match (&v[0], &v[1]) {
+                    (Parameters::Float(value), Parameters::Float(err)) => {
+                        assert!((value - expected).abs() < 1e-8, "{input} = {value}");
+                        assert!(*err < 1e-8);
+                    }
+                    p => panic!("expected floats, got {p:?}"),
+                },
+                p => panic!("expected a vector, got {p}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpreter_solve_rational_roots() {
+        assert_eq!(eval("solve(x^2 - 1, x)"), vector(vec![-1, 1]));
+        assert_eq!(
+            eval("solve(2*x + 1 == 0, x)"),
+            Parameters::InterpreterVector(Box::from(vec![Parameters::Rational(Rationals::new(
+                2, -1
+            ))]))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_irrational_roots() {
+        assert_eq!(
+            eval("solve(x^2 == 2, x)"),
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Float(-2f64.sqrt()),
+                Parameters::Float(2f64.sqrt())
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_complex_roots() {
+        assert_eq!(
+            eval("solve(x^4 == 1, x)"),
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Int(-1),
+                Parameters::Int(1),
+                Parameters::Complex(Complex64::new(0.0, -1.0)),
+                Parameters::Complex(Complex64::new(0.0, 1.0))
+            ]))
+        );
+        assert_eq!(
+            eval("solve(x^3 == 8, x)"),
+            Parameters::InterpreterVector(Box::from(vec![
+                Parameters::Int(2),
+                Parameters::Complex(Complex64::new(-1.0, -3f64.sqrt())),
+                Parameters::Complex(Complex64::new(-1.0, 3f64.sqrt()))
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_numeric() {
+        assert_close(eval("solve(cos(x) == x, x)"), 0.7390851332151607);
+    }
+
+    #[test]
+    fn test_interpreter_solve_function() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("f(x) = x^2 - 4", &mut ram, &mut function);
+        assert_eq!(
+            run("solve(f, 3)", &mut ram, &mut function),
+            Parameters::Int(2)
+        );
+        assert_eq!(
+            run("bisect(f, 0, 3)", &mut ram, &mut function),
+            Parameters::Int(2)
+        );
+    }
+
+    #[test]
+    fn test_interpreter_bisect_lambda() {
+        assert_close(eval("bisect(x -> x^3 - 2, 0, 2)"), 2f64.cbrt());
+    }
+
+    #[test]
+    fn test_interpreter_solve_shadows_variable() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("x = 5", &mut ram, &mut function);
+        assert_eq!(
+            run("solve(x^2 == 9, x)", &mut ram, &mut function),
+            vector(vec![-3, 3])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_linear() {
+        assert_eq!(
+            eval("solve_linear([[2,1],[1,3]], [3,5])"),
+            fractions(vec![(4, 5), (7, 5)])
+        );
+        assert_eq!(
+            eval("solve_linear([[1,1,1],[0,2,5],[2,5,-1]], [6,-4,27])"),
+            fractions(vec![(5, 1), (3, 1), (-2, 1)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_linear_pivoting() {
+        assert_eq!(
+            eval("solve_linear([[0,1],[1,0]], [2,3])"),
+            fractions(vec![(3, 1), (2, 1)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_linear_matrix() {
+        assert_eq!(
+            eval("solve_linear([[1,2],[3,4]], [[1,0],[0,1]])"),
+            Parameters::InterpreterVector(Box::from(vec![
+                fractions(vec![(-2, 1), (1, 1)]),
+                fractions(vec![(3, 2), (-1, 2)])
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_linear_least_squares() {
+        assert_eq!(
+            eval("solve_linear([[1,1],[1,2],[1,3],[1,4]], [6,5,7,10])"),
+            fractions(vec![(7, 2), (7, 5)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_solve_linear_singular() {
+        assert_error(
+            eval("solve_linear([[1,2],[2,4]], [5,6])"),
+            ErrorKind::NotInvertible,
+        );
+    }
+
+    #[test]
+    fn test_interpreter_trace() {
+        assert_eq!(eval("trace([[1,2],[3,4]])"), Parameters::Int(5));
+    }
+
+    #[test]
+    fn test_interpreter_rank() {
+        assert_eq!(eval("rank([[1,2,3],[4,5,6],[7,8,9]])"), Parameters::Int(2));
+    }
+
+    #[test]
+    fn test_interpreter_kernel() {
+        let expected = list(vec![list(vec![
+            rational(1, 1),
+            rational(-2, 1),
+            Parameters::Int(1),
+        ])]);
+        assert_eq!(eval("kernel([[1,2,3],[4,5,6],[7,8,9]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_lu() {
+        let expected = list(vec![
+            list(vec![
+                vector(vec![1, 0]),
+                list(vec![rational(1, 3), Parameters::Int(1)]),
+            ]),
+            list(vec![
+                vector(vec![3, 4]),
+                list(vec![Parameters::Int(0), rational(2, 3)]),
+            ]),
+            list(vec![vector(vec![0, 1]), vector(vec![1, 0])]),
+        ]);
+        assert_eq!(eval("lu([[1,2],[3,4]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_lu_singular() {
+        let expected = list(vec![
+            list(vec![
+                vector(vec![1, 0]),
+                list(vec![rational(1, 2), Parameters::Int(1)]),
+            ]),
+            list(vec![
+                vector(vec![2, 4]),
+                list(vec![Parameters::Int(0), rational(0, 1)]),
+            ]),
+            list(vec![vector(vec![0, 1]), vector(vec![1, 0])]),
+        ]);
+        assert_eq!(eval("lu([[1,2],[2,4]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_eig() {
+        let expected = list(vec![
+            vector(vec![1, 3]),
+            list(vec![
+                list(vec![rational(-1, 1), Parameters::Int(1)]),
+                list(vec![rational(1, 1), Parameters::Int(1)]),
+            ]),
+        ]);
+        assert_eq!(eval("eig([[2,1],[1,2]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_eig_complex() {
+        let (i, minus_i) = (Complex64::new(0.0, 1.0), Complex64::new(0.0, -1.0));
+        let expected = list(vec![
+            list(vec![Parameters::Complex(minus_i), Parameters::Complex(i)]),
+            list(vec![
+                list(vec![Parameters::Complex(minus_i), Parameters::Int(1)]),
+                list(vec![Parameters::Complex(i), Parameters::Int(1)]),
+            ]),
+        ]);
+        assert_eq!(eval("eig([[0,-1],[1,0]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_eig_repeated() {
+        let expected = list(vec![
+            vector(vec![2, 2, 3]),
+            list(vec![
+                vector(vec![1, 0, 0]),
+                vector(vec![0, 1, 0]),
+                list(vec![rational(0, 1), rational(0, 1), Parameters::Int(1)]),
+            ]),
+        ]);
+        assert_eq!(eval("eig([[2,0,0],[0,2,0],[0,0,3]])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_eig_defective() {
+        assert_error(eval("eig([[1,1],[0,1]])"), ErrorKind::NotInvertible);
+    }
+
+    #[test]
+    fn test_interpreter_qr() {
+        assert_close_matrix(
+            eval("qr([[12,-51,4],[6,167,-68],[-4,24,-41]])[1]"),
+            vec![
+                vec![14.0, 21.0, -14.0],
+                vec![0.0, 175.0, -70.0],
+                vec![0.0, 0.0, 35.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_interpreter_svd() {
+        assert_close(eval("svd([[3,0],[4,5]])[1][0]"), 45.0_f64.sqrt());
+        assert_close(eval("svd([[3,0],[4,5]])[1][1]"), 5.0_f64.sqrt());
+    }
+
+    #[test]
+    fn test_interpreter_svd_zero_matrix() {
+        assert_close_matrix(
+            eval("svd([[0,0],[0,0]])[0]"),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+        );
+        assert_close_matrix(
+            eval("svd([[0,0],[0,0],[0,0]])[0]"),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 0.0]],
+        );
+    }
+
+    #[test]
+    fn test_interpreter_sum() {
+        assert_eq!(eval("sum([1,2,3,4])"), Parameters::Int(10));
+    }
+
+    #[test]
+    fn test_interpreter_sum_series() {
+        assert_eq!(eval("sum(k^2, k, 1, 3)"), Parameters::Int(14));
+    }
+
+    #[test]
+    fn test_interpreter_sum_of_values() {
+        assert_eq!(eval("sum(1,2,3)"), Parameters::Int(6));
+        assert_eq!(eval("sum(1,2,3,4)"), Parameters::Int(10));
+    }
+
+    #[test]
+    fn test_interpreter_cumsum() {
+        assert_eq!(eval("cumsum([1,2,3])"), vector(vec![1, 3, 6]));
+        assert_eq!(
+            eval("cumsum([[1,2],[3,4]])"),
+            list(vec![vector(vec![1, 2]), vector(vec![4, 6])])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_mean() {
+        assert_eq!(eval("mean([1,2,3,4])"), rational(5, 2));
+        assert_eq!(
+            eval("mean([[1,2],[3,4]])"),
+            list(vec![rational(2, 1), rational(3, 1)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_mean_empty() {
+        assert_error(eval("mean([])"), ErrorKind::WrongArguments);
+    }
+
+    #[test]
+    fn test_interpreter_median_and_mode() {
+        assert_eq!(eval("median([4,1,3,2])"), rational(5, 2));
+        assert_eq!(eval("mode([1,2,2,3,3,3,1])"), Parameters::Int(3));
+    }
+
+    #[test]
+    fn test_interpreter_min_and_max() {
+        assert_eq!(eval("min([3,1/2,2])"), rational(1, 2));
+        assert_eq!(eval("max(3,1,7)"), Parameters::Int(7));
+    }
+
+    #[test]
+    fn test_interpreter_quantile() {
+        assert_eq!(
+            eval("quantile([1,2,3,4,5], [0, 1/4, 1])"),
+            list(vec![Parameters::Int(1), rational(2, 1), Parameters::Int(5)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_variance() {
+        assert_eq!(eval("var([1,2,3,4])"), rational(5, 3));
+        assert_close(eval("std([2,4,4,4,5,5,7,9])"), (32.0_f64 / 7.0).sqrt());
+    }
+
+    #[test]
+    fn test_interpreter_covariance() {
+        assert_eq!(eval("cov([1,2,3],[1,2,4])"), rational(3, 2));
+        assert_close(eval("corr([1,2,3],[2,4,6])"), 1.0);
+    }
+
+    #[test]
+    fn test_interpreter_linreg() {
+        let expected = list(vec![
+            list(vec![rational(0, 1), rational(19, 10)]),
+            rational(361, 375),
+        ]);
+        assert_eq!(eval("linreg([1,2,3,4],[2,4,5,8])"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_polyfit() {
+        let expected = list(vec![
+            list(vec![rational(1, 1), rational(0, 1), rational(1, 1)]),
+            rational(1, 1),
+        ]);
+        assert_eq!(eval("polyfit([0,1,2,3],[1,2,5,10],2)"), expected);
+    }
+
+    #[test]
+    fn test_interpreter_polyfit_same_x() {
+        assert_error(
+            eval("polyfit([1,1,1],[1,2,3],1)"),
+            ErrorKind::WrongArguments,
+        );
+    }
+
+    #[test]
+    fn test_interpreter_poly() {
+        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut function: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+        run("g = poly([1,2,3])", &mut ram, &mut function);
+        assert_eq!(run("g(2)", &mut ram, &mut function), Parameters::Int(17));
+        assert_eq!(run("g(0)", &mut ram, &mut function), Parameters::Int(1));
+    }
+
+    #[test]
+    fn test_interpreter_poly_of_polyfit() {
+        assert_eq!(
+            eval("poly(polyfit([0,1,2,3],[1,2,5,10],2))(4)"),
+            Parameters::Int(17)
+        );
+    }
+
+    #[test]
+    fn test_interpreter_normal_distribution() {
+        assert_close(eval("normpdf(0)"), 0.3989422804014327);
+        assert_close(eval("normcdf(1.96)"), 0.9750021048517795);
+        assert_close(eval("norminv(0.975)"), 1.959963984540054);
+        assert_close(eval("norminv(0.5, 10, 2)"), 10.0);
+    }
+
+    #[test]
+    fn test_interpreter_normal_inverse_bounds() {
+        assert_eq!(eval("norminv(1)"), Parameters::Float(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_interpreter_exponential_distribution() {
+        assert_close(eval("exppdf(1, 2)"), 0.2706705664732254);
+        assert_close(eval("expinv(0.5)"), std::f64::consts::LN_2);
+    }
+
+    #[test]
+    fn test_interpreter_student_distribution() {
+        assert_close(eval("tcdf(2, 5)"), 0.9490302605850709);
+        assert_close(eval("tinv(0.975, 10)"), 2.228138851986274);
+    }
+
+    #[test]
+    fn test_interpreter_chi2_distribution() {
+        assert_close(eval("chi2cdf(3.84, 1)"), 0.9499564787512006);
+        assert_close(eval("chi2inv(0.95, 2)"), 5.991464547107979);
+    }
+
+    #[test]
+    fn test_interpreter_poisson_distribution() {
+        assert_close(eval("poissonpdf(2, 3)"), 0.22404180765538775);
+        assert_close(eval("poissoncdf(2, 3)"), 0.42319008112684364);
+    }
+
+    #[test]
+    fn test_interpreter_binomial_distribution() {
+        assert_eq!(eval("binompdf(2, 4, 1/2)"), rational(3, 8));
+        assert_eq!(eval("binomcdf(2, 4, 1/2)"), rational(11, 16));
+        assert_eq!(
+            eval("binompdf([0,1,2], 2, 1/3)"),
+            list(vec![rational(4, 9), rational(4, 9), rational(1, 9)])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_binomial_distribution_large() {
+        assert_close(eval("binomcdf(3, 5000, 0.001)"), 0.2648854759);
+    }
+
+    #[test]
+    fn test_interpreter_gamma() {
+        assert_eq!(eval("gamma(6)"), Parameters::Int(120));
+        assert_close(eval("gamma(0.5)"), std::f64::consts::PI.sqrt());
+        assert_close(eval("lgamma(100)"), 359.1342053695754);
+    }
+
+    #[test]
+    fn test_interpreter_gamma_poles() {
+        assert_error(eval("gamma(-2)"), ErrorKind::WrongArguments);
+    }
+
+    #[test]
+    fn test_interpreter_fact() {
+        assert_close(eval("fact(1/2)"), std::f64::consts::PI.sqrt() / 2.0);
+        assert_eq!(eval("fact([0,3,4.0])"), vector(vec![1, 6, 24]));
+    }
+
+    #[test]
+    fn test_interpreter_beta() {
+        assert_eq!(eval("beta(2, 3)"), rational(1, 12));
+        assert_close(eval("beta(0.5, 0.5)"), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_interpreter_erf() {
+        assert_close(eval("erf(1)"), 0.8427007929497149);
+        assert_close(eval("erfc(2)"), 0.004677734981047266);
+    }
+
+    #[test]
+    fn test_interpreter_zeta() {
+        assert_close(
+            eval("zeta(2)"),
+            std::f64::consts::PI * std::f64::consts::PI / 6.0,
+        );
+    }
+
+    #[test]
+    fn test_interpreter_bessel() {
+        assert_close(eval("besselj(0, 1)"), 0.7651976865579666);
+        assert_close(eval("besselj(2, 10)"), 0.2546303137);
+        assert_close(eval("bessely(1, 3)"), 0.3246744247918);
+    }
+
+    #[test]
+    fn test_interpreter_gcd_and_lcm() {
+        assert_eq!(eval("gcd(12, 18)"), Parameters::Int(6));
+        assert_eq!(eval("gcd([12,18,24])"), Parameters::Int(6));
+        assert_eq!(eval("lcm(4, 6, 10)"), Parameters::Int(60));
+    }
+
+    #[test]
+    fn test_interpreter_gcd_and_lcm_big() {
+        assert_eq!(eval("gcd(2^70, 2^65*3)"), eval("2^65"));
+        assert_eq!(eval("lcm(2^70, 3)"), eval("3*2^70"));
+    }
+
+    #[test]
+    fn test_interpreter_arrangements() {
+        assert_eq!(eval("nCr(52, 5)"), Parameters::Int(2598960));
+        assert_eq!(eval("binomial([4,5], 2)"), vector(vec![6, 10]));
+        assert_eq!(eval("nPr(5, 2)"), Parameters::Int(20));
+    }
+
+    #[test]
+    fn test_interpreter_primes() {
+        assert_eq!(
+            eval("isprime([2,9,97])"),
+            list(vec![
+                Parameters::Bool(true),
+                Parameters::Bool(false),
+                Parameters::Bool(true)
+            ])
+        );
+        assert_eq!(eval("nextprime(100)"), Parameters::Int(101));
+        assert_eq!(eval("totient(36)"), Parameters::Int(12));
+    }
+
+    #[test]
+    fn test_interpreter_primes_big() {
+        assert_eq!(eval("isprime(2^89 - 1)"), Parameters::Bool(true));
+        assert_eq!(eval("isprime(2^89 + 1)"), Parameters::Bool(false));
+        assert_eq!(eval("nextprime(2^64)"), eval("2^64 + 13"));
+        assert_eq!(eval("totient(2^70)"), eval("2^69"));
+    }
+
+    #[test]
+    fn test_interpreter_factor_integer() {
+        assert_eq!(
+            eval("factor(360)"),
+            list(vec![
+                vector(vec![2, 3]),
+                vector(vec![3, 2]),
+                vector(vec![5, 1])
+            ])
+        );
+        assert_eq!(
+            eval("factor(-12)"),
+            list(vec![
+                vector(vec![-1, 1]),
+                vector(vec![2, 2]),
+                vector(vec![3, 1])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_factor_big_integer() {
+        assert_eq!(eval("factor(2^70)"), list(vec![vector(vec![2, 70])]));
+        assert_eq!(
+            eval("factor(998244353 * 1000000007 * 1000000009)"),
+            list(vec![
+                vector(vec![998244353, 1]),
+                vector(vec![1000000007, 1]),
+                vector(vec![1000000009, 1])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_interpreter_factor_not_integer() {
+        assert_error(eval("factor(1/2)"), ErrorKind::WrongArguments);
+        assert_error(eval("factor(2.5)"), ErrorKind::WrongArguments);
+    }
+
+    #[test]
+    fn test_interpreter_factor_polynomial() {
+        assert_eq!(
+            factors(eval("factor(x^2 - 1)")),
+            factors(eval("factor((x + 1)*(x - 1))"))
+        );
+    }
+
+    #[test]
+    fn test_interpreter_modular() {
+        assert_eq!(eval("modpow(4, 13, 497)"), Parameters::Int(445));
+        assert_eq!(eval("modpow(3, -1, 11)"), Parameters::Int(4));
+        assert_eq!(eval("modinv(3, 11)"), Parameters::Int(4));
+        assert_eq!(eval("mod(-7, 3)"), Parameters::Int(2));
+    }
+
+    #[test]
+    fn test_interpreter_modular_big() {
+        assert_eq!(eval("mod(2^70, 1000)"), Parameters::Int(424));
+        assert_eq!(
+            eval("modpow(2, 2^70, 1000000007)"),
+            Parameters::Int(100126750)
+        );
+        assert_eq!(eval("modinv(2^70, 1000000007)"), Parameters::Int(816690681));
+    }
+
+    #[test]
+    fn test_interpreter_modinv_not_invertible() {
+        assert_error(eval("modinv(2, 4)"), ErrorKind::WrongArguments);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod function;
 pub(crate) mod interpreter;
 pub mod stdlib;
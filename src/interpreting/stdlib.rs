@@ -1,15 +1,40 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::f64::consts::{E, PI};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use gnuplot::{AxesCommon, Figure};
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
 
 use crate::configuration::loader::{load, load_config, Config};
-use crate::interpreting::interpreter::interpret;
-use crate::parsing::ast::{Ast, Parameters};
-use crate::utils::matrix_utils::{lup_decompose, lup_determinant, lup_invert, transpose};
+use crate::exact_math::algebra::{free_variable, is_coefficient, Expression, Polynomial};
+use crate::exact_math::rationals::Rationals;
+use crate::exact_math::symbolic::{self, differentiate};
+use crate::interpreting::error::{
+    error, incompatible_argument, unknown_function, unknown_variable, wrong_arguments, CalcError,
+    ErrorKind,
+};
+use crate::interpreting::interpreter::{capture, interpret};
+use crate::parsing::ast::{Ast, Parameters, Position};
+use crate::utils::complex_utils::{complex_result, is_negative_real, to_complex};
+use crate::utils::integer_utils::{
+    binomial as combinations, factorize, is_prime, lcm, mod_inverse, mod_pow, next_prime,
+    permutations, totient,
+};
+use crate::utils::matrix_utils::{
+    characteristic_polynomial, householder_qr, jacobi_svd, kernel, lup_decompose, lup_determinant,
+    lup_invert, lup_solve, normal_equations, row_echelon, trace, transpose,
+};
 use crate::utils::plot_utils::computes_lines;
+use crate::utils::special_utils::{
+    bessel_j, bessel_y, beta, beta_regularized, erf, erfc, gamma, gamma_p, gamma_q,
+    invert_increasing, ln_gamma, zeta,
+};
 
-use super::function::{add as other_add, mult};
+use super::function::{add as other_add, divide, minus, mult};
 
 pub fn exec(
     s: String,
@@ -34,6 +59,11 @@ pub fn exec(
         "fact" => factorial(&lst, &ram),
         "factorial" => factorial(&lst, &ram),
         "abs" => abs(&lst, &ram),
+        "re" => re(&lst, &ram),
+        "float" => float(&lst, &ram),
+        "im" => im(&lst, &ram),
+        "arg" => arg(&lst, &ram),
+        "conj" => conj(&lst, &ram),
         "ceil" => ceil(&lst, &ram),
         "floor" => floor(&lst, &ram),
         "round" => round(&lst, &ram),
@@ -42,51 +72,234 @@ pub fn exec(
         "transpose" => transpose_matrices(&lst, &ram),
         "det" => det_matrix(&lst, &ram),
         "invert" => inverse_matrix(&lst, &ram),
+        "solve_linear" => solve_linear(&lst, &ram),
+        "trace" => trace_matrix(&lst, &ram),
+        "rank" => rank_matrix(&lst, &ram),
+        "kernel" => kernel_matrix("kernel", &lst, &ram),
+        "nullspace" => kernel_matrix("nullspace", &lst, &ram),
+        "lu" => lu_matrix(&lst, &ram),
+        "qr" => qr_matrix(&lst, &ram),
+        "svd" => svd_matrix(&lst, &ram),
+        "eig" => eig_matrix(&lst, &ram),
         "plot" => plot_fn(&lst, &ram, functions, false),
         "termplot" => plot_fn(&lst, &ram, functions, true),
-        s => {
-            let mut sram: HashMap<String, Parameters> = HashMap::new();
-            sram.insert("pi".to_string(), Parameters::Float(PI));
-            sram.insert("e".to_string(), Parameters::Float(E));
-            match functions.cloned() {
-                None => Parameters::Identifier("This function is unknown".to_string()),
-                Some(mut f) => {
-                    let fs = f.get_mut(s);
-                    let (vec, ast): (&mut Vec<Ast>, &mut Ast) = match fs {
-                        None => {
-                            return Parameters::Identifier("This function is unknown".to_string());
-                        }
-                        Some((a, b)) => (a, b),
-                    };
-
-                    let mut names = Vec::new();
-                    for v in vec {
-                        match v {
-                            Ast::Nil => (),
-                            Ast::Call { .. } => (),
-                            Ast::Node {
-                                value: v,
-                                left: _l,
-                                right: _r,
-                            } => match v {
-                                Parameters::Identifier(s) => names.push(s.clone()),
-                                _ => (),
-                            },
-                        }
-                    }
-                    names.iter().zip(lst).for_each(|(name, param)| {
-                        sram.insert(name.to_string(), param);
-                    });
-                    interpret(ast, &mut sram, &mut HashMap::new())
-                }
+        "map" => map(&lst, ram, functions),
+        "apply" => apply(&lst, ram, functions),
+        "filter" => filter(&lst, ram, functions),
+        "reduce" => reduce(&lst, ram, functions),
+        "fold" => fold(&lst, ram, functions),
+        "zip" => zip(&lst, &ram),
+        "range" => range(&lst, &ram),
+        "linspace" => linspace(&lst, &ram),
+        "integrate" => integrate(&lst, ram, functions),
+        "bisect" => bisect(&lst, ram, functions),
+        "sum" => statistic("sum", &lst, &ram, total),
+        "cumsum" => statistic("cumsum", &lst, &ram, cumulative_sum),
+        "mean" => statistic("mean", &lst, &ram, mean_of),
+        "median" => statistic("median", &lst, &ram, median_of),
+        "mode" => statistic("mode", &lst, &ram, mode_of),
+        "var" => statistic("var", &lst, &ram, variance_of),
+        "std" => statistic("std", &lst, &ram, standard_deviation_of),
+        "min" => statistic("min", &lst, &ram, |v| extremum("min", v, false)),
+        "max" => statistic("max", &lst, &ram, |v| extremum("max", v, true)),
+        "quantile" => quantile(&lst, &ram),
+        "cov" => covariance("cov", &lst, &ram),
+        "linreg" => linreg(&lst, &ram),
+        "polyfit" => polyfit(&lst, &ram),
+        "poly" => poly(&lst, &ram),
+        "corr" => covariance("corr", &lst, &ram),
+        "normpdf" | "normcdf" | "norminv" => normal(&s, &lst, &ram),
+        "exppdf" | "expcdf" | "expinv" => exponential(&s, &lst, &ram),
+        "tpdf" | "tcdf" | "tinv" => student(&s, &lst, &ram),
+        "chi2pdf" | "chi2cdf" | "chi2inv" => chi_squared(&s, &lst, &ram),
+        "poissonpdf" | "poissoncdf" => poisson(&s, &lst, &ram),
+        "binompdf" | "binomcdf" => binomial(&s, &lst, &ram),
+        "gamma" | "lgamma" | "erf" | "erfc" | "zeta" => special_function(&s, &lst, &ram),
+        "beta" => beta_function(&lst, &ram),
+        "besselj" | "bessely" => bessel(&s, &lst, &ram),
+        "simplify" => normal_form("simplify", &lst, functions),
+        "expand" => normal_form("expand", &lst, functions),
+        "factor" if is_integer_argument(lst.first(), &ram) => prime_factors(&lst, &ram),
+        "factor" => normal_form("factor", &lst, functions),
+        "gcd" | "lcm" => common_divisor(&s, &lst, &ram),
+        "nCr" | "binomial" | "nPr" => arrangements(&s, &lst, &ram),
+        "isprime" | "nextprime" | "totient" => prime_function(&s, &lst, &ram),
+        "mod" | "modpow" | "modinv" => modular(&s, &lst, &ram),
+        s => match (ram.as_ref().and_then(|r| r.get(s)), functions) {
+            (Some(Parameters::Lambda(params, body)), Some(f)) => {
+                call_lambda(&params.clone(), &body.clone(), lst, f)
             }
+            (_, None) => unknown_function(s),
+            (_, Some(f)) => call_user_function(s, lst, f),
+        },
+    }
+}
+
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 1000;
+
+static MAX_RECURSION_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_RECURSION_DEPTH);
+
+thread_local! {
+    static RECURSION_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static RECURSION_OVERFLOW: Cell<Option<usize>> = const { Cell::new(None) };
+    static STACK_BOUNDS: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+pub fn set_max_recursion_depth(depth: usize) {
+    MAX_RECURSION_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+pub fn set_stack_size(size: usize) {
+    let marker = 0u8;
+    let start = &marker as *const u8 as usize;
+    STACK_BOUNDS.with(|b| b.set(Some((start, size))));
+}
+
+fn stack_exhausted() -> bool {
+    let marker = 0u8;
+    let here = &marker as *const u8 as usize;
+    STACK_BOUNDS.with(|b| match b.get() {
+        Some((start, size)) => start.abs_diff(here) > size - size / 8,
+        None => false,
+    })
+}
+
+fn recursion_error(depth: usize) -> Parameters {
+    let max = MAX_RECURSION_DEPTH.load(Ordering::Relaxed);
+    error(
+        ErrorKind::RecursionLimit,
+        match depth > max {
+            true => format!("Maximum recursion depth of {max} exceeded"),
+            false => format!("The stack is full after a recursion depth of {depth}"),
+        },
+    )
+}
+
+pub fn exec_lazy(
+    s: &str,
+    lst: &Vec<Ast>,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Option<Parameters> {
+    match (s, lst.len()) {
+        ("sum", 3 | 4) if variable_name(&lst[1]).is_some() => Some(series(
+            "sum",
+            lst,
+            ram,
+            functions,
+            Parameters::Int(0),
+            other_add,
+        )),
+        ("prod", 3 | 4) => Some(series(
+            "prod",
+            lst,
+            ram,
+            functions,
+            Parameters::Int(1),
+            mult,
+        )),
+        ("diff", 1..=3) => Some(diff(lst, ram, functions)),
+        ("solve", 2 | 3) => Some(solve(lst, ram, functions)),
+        _ => None,
+    }
+}
+
+pub fn call_user_function(
+    s: &str,
+    lst: Vec<Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    let (vec, ast): (Vec<Ast>, Ast) = match functions.get(s) {
+        None => {
+            return unknown_function(s);
+        }
+        Some((a, b)) => (a.clone(), b.clone()),
+    };
+
+    let mut names = Vec::new();
+    for v in vec {
+        match v {
+            Ast::Nil => (),
+            Ast::Call { .. } => (),
+            Ast::If { .. } => (),
+            Ast::Index { .. } => (),
+            Ast::Node {
+                value: v,
+                left: _l,
+                right: _r,
+                ..
+            } => match v {
+                Parameters::Identifier(s) => names.push(s.clone()),
+                _ => (),
+            },
         }
     }
+    call_lambda(&names, &ast, lst, functions)
+}
+
+pub fn call_lambda(
+    names: &Vec<String>,
+    ast: &Ast,
+    lst: Vec<Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    if let Some(depth) = RECURSION_OVERFLOW.with(|o| o.get()) {
+        return recursion_error(depth);
+    }
+
+    if names.len() != lst.len() {
+        return error(
+            ErrorKind::WrongArguments,
+            format!(
+                "The function takes {} argument(s) but {} were given",
+                names.len(),
+                lst.len()
+            ),
+        );
+    }
+
+    let depth = RECURSION_DEPTH.with(|d| {
+        d.set(d.get() + 1);
+        d.get()
+    });
+
+    if depth > MAX_RECURSION_DEPTH.load(Ordering::Relaxed) || stack_exhausted() {
+        RECURSION_DEPTH.with(|d| d.set(d.get() - 1));
+        if depth > 1 {
+            RECURSION_OVERFLOW.with(|o| o.set(Some(depth)));
+        }
+        return recursion_error(depth);
+    }
+
+    let mut sram: HashMap<String, Parameters> = HashMap::new();
+    sram.insert("pi".to_string(), Parameters::Float(PI));
+    sram.insert("e".to_string(), Parameters::Float(E));
+    sram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
+
+    names.iter().zip(lst).for_each(|(name, param)| {
+        sram.insert(name.to_string(), free_variable(param));
+    });
+    let result = match interpret(ast, &mut sram, functions) {
+        Parameters::Error(e) => Parameters::Error(CalcError {
+            position: None,
+            span: None,
+            ..e
+        }),
+        p => p,
+    };
+
+    RECURSION_DEPTH.with(|d| d.set(d.get() - 1));
+    if depth == 1 {
+        if let Some(overflow) = RECURSION_OVERFLOW.with(|o| o.take()) {
+            return recursion_error(overflow);
+        }
+    }
+    result
 }
 
 pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("cos");
     }
 
     let mut degrees = false;
@@ -119,6 +332,11 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             };
             Parameters::Float(fs.cos())
         }
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).cos()
+        } else {
+            c.cos()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -137,6 +355,13 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().cos()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(cos(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(cos(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -155,9 +380,9 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         cos(
@@ -170,13 +395,13 @@ pub fn cos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("cos"),
     }
 }
 
 pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("sin");
     }
 
     let mut degrees = false;
@@ -209,6 +434,11 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             };
             Parameters::Float(fs.sin())
         }
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).sin()
+        } else {
+            c.sin()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -227,6 +457,13 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().sin()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(sin(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(sin(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -245,9 +482,9 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         sin(
@@ -260,13 +497,13 @@ pub fn sin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("sin"),
     }
 }
 
 pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("tan");
     }
 
     let mut degrees = false;
@@ -300,6 +537,11 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::Float(fs.tan())
         }
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).tan()
+        } else {
+            c.tan()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -318,6 +560,13 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().tan()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(tan(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(tan(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -336,9 +585,9 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         tan(
@@ -351,13 +600,13 @@ pub fn tan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("tan"),
     }
 }
 
 pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("cosh");
     }
 
     let mut degrees = false;
@@ -391,6 +640,11 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::Float(fs.cosh())
         }
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).cosh()
+        } else {
+            c.cosh()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -409,6 +663,13 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().cosh()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(cosh(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(cosh(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -427,9 +688,9 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         cosh(
@@ -442,13 +703,13 @@ pub fn cosh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("cosh"),
     }
 }
 
 pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("sinh");
     }
 
     let mut degrees = false;
@@ -482,6 +743,11 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::Float(fs.sinh())
         }
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).sinh()
+        } else {
+            c.sinh()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -500,6 +766,13 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().sinh()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(sinh(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(sinh(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -518,9 +791,9 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         sinh(
@@ -533,13 +806,13 @@ pub fn sinh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("sinh"),
     }
 }
 
 pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("tanh");
     }
 
     let mut degrees = false;
@@ -573,6 +846,11 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::Float(fs.tanh())
         }
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            (c * PI / 180.0).tanh()
+        } else {
+            c.tanh()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -591,6 +869,13 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().tanh()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(tanh(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(tanh(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -609,9 +894,9 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         tanh(
@@ -624,13 +909,13 @@ pub fn tanh(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("tanh"),
     }
 }
 
 pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("acos");
     }
 
     let mut degrees = false;
@@ -662,6 +947,11 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             s.clone().approx().acos()
         }),
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            c.acos() * 180.0 / PI
+        } else {
+            c.acos()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -680,6 +970,13 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().acos()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(acos(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(acos(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -698,9 +995,9 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         acos(
@@ -713,13 +1010,13 @@ pub fn acos(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("acos"),
     }
 }
 
 pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("asin");
     }
 
     let mut degrees = false;
@@ -752,6 +1049,11 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             s.clone().approx().asin()
         }),
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            c.asin() * 180.0 / PI
+        } else {
+            c.asin()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -770,6 +1072,13 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().asin()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(asin(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(asin(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -788,9 +1097,9 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         asin(
@@ -803,13 +1112,13 @@ pub fn asin(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("asin"),
     }
 }
 
 pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("atan");
     }
 
     let mut degrees = false;
@@ -842,6 +1151,11 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             s.clone().approx().atan()
         }),
 
+        Parameters::Complex(c) => complex_result(if degrees {
+            c.atan() * 180.0 / PI
+        } else {
+            c.atan()
+        }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -860,6 +1174,13 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().atan()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if degrees {
+                        res.push(atan(&vec![c, Parameters::Bool(false)], ram))
+                    } else {
+                        res.push(atan(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -878,9 +1199,9 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => {
                     if degrees {
                         atan(
@@ -893,13 +1214,13 @@ pub fn atan(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 }
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("atan"),
     }
 }
 
 pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("exp");
     }
 
     let mut plus = false;
@@ -943,6 +1264,7 @@ pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             }
         }
 
+        Parameters::Complex(c) => complex_result(if plus { c.expf(ln) } else { c.exp() }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
@@ -959,6 +1281,13 @@ pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.approx().exp()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if plus {
+                        res.push(exp(&vec![c, Parameters::Float(ln)], ram))
+                    } else {
+                        res.push(exp(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -977,19 +1306,19 @@ pub fn exp(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => exp(&vec![t.clone(), Parameters::Float(ln)], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("exp"),
     }
 }
 
 pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("ln");
     }
 
     let mut plus = false;
@@ -1010,6 +1339,14 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
     }
 
     match p.get(0).unwrap() {
+        x if is_negative_real(x) => {
+            let c = Parameters::Complex(to_complex(x).unwrap());
+            if plus {
+                ln(&vec![c, Parameters::Float(sln)], ram)
+            } else {
+                ln(&vec![c], ram)
+            }
+        }
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -1034,9 +1371,17 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
             }
         }
 
+        Parameters::Complex(c) => complex_result(if plus { c.log(sln) } else { c.ln() }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
+                x if is_negative_real(&x) => {
+                    if plus {
+                        res.push(ln(&vec![x, Parameters::Float(sln)], ram))
+                    } else {
+                        res.push(ln(&vec![x], ram))
+                    }
+                }
                 Parameters::Int(i) => res.push(Parameters::Float(if plus {
                     (i as f64).log(sln)
                 } else {
@@ -1050,6 +1395,13 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
                 } else {
                     s.approx().ln()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if plus {
+                        res.push(ln(&vec![c, Parameters::Float(sln)], ram))
+                    } else {
+                        res.push(ln(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -1068,19 +1420,19 @@ pub fn ln(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => ln(&vec![t.clone(), Parameters::Float(sln)], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("ln"),
     }
 }
 
 pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("sqrt");
     }
 
     let mut plus = false;
@@ -1101,6 +1453,14 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
     }
 
     match p.get(0).unwrap() {
+        x if is_negative_real(x) => {
+            let c = Parameters::Complex(to_complex(x).unwrap());
+            if plus {
+                sqrt(&vec![c, Parameters::Float(sln)], ram)
+            } else {
+                sqrt(&vec![c], ram)
+            }
+        }
         Parameters::Int(i) => {
             let fs: f64 = (*i) as f64;
             if plus {
@@ -1124,9 +1484,17 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             }
         }
 
+        Parameters::Complex(c) => complex_result(if plus { c.powf(1.0 / sln) } else { c.sqrt() }),
         Parameters::InterpreterVector(vec) => {
             let mut res = Vec::new();
             vec.clone().into_iter().for_each(|x| match x {
+                x if is_negative_real(&x) => {
+                    if plus {
+                        res.push(sqrt(&vec![x, Parameters::Float(sln)], ram))
+                    } else {
+                        res.push(sqrt(&vec![x], ram))
+                    }
+                }
                 Parameters::Int(i) => res.push(Parameters::Float(if plus {
                     (i as f64).powf(1.0 / sln)
                 } else {
@@ -1142,6 +1510,13 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
                 } else {
                     s.clone().approx().sqrt()
                 })),
+                c @ Parameters::Complex(_) => {
+                    if plus {
+                        res.push(sqrt(&vec![c, Parameters::Float(sln)], ram))
+                    } else {
+                        res.push(sqrt(&vec![c], ram))
+                    }
+                }
                 Parameters::Identifier(s) => match ram {
                     None => (),
                     Some(ref t) => match t.get(s.as_str()) {
@@ -1160,24 +1535,18 @@ pub fn sqrt(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>)
             Parameters::InterpreterVector(Box::from(res))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => sqrt(&vec![t.clone(), Parameters::Float(sln)], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("sqrt"),
     }
 }
 
-pub fn fact(n: i64) -> i64 {
-    fn aux(n: i64, acc: i64) -> i64 {
-        match n {
-            0 => acc,
-            i => aux(i - 1, i * acc),
-        }
-    }
-    aux(n, 1)
+pub fn fact(n: i64) -> BigInt {
+    (1..=n).fold(BigInt::from(1), |acc, i| acc * i)
 }
 
 pub fn factorial(
@@ -1185,84 +1554,210 @@ pub fn factorial(
     ram: &Option<&mut HashMap<String, Parameters>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("fact");
+    }
+    distribution("fact", "(n)", p, ram, 0, &[], |n, _| {
+        let integer = match n {
+            Parameters::Int(i) => Some(*i),
+            Parameters::Rational(r) => r.to_int(),
+            Parameters::Float(f) if *f == f.floor() && f.abs() <= i64::MAX as f64 => {
+                Some(*f as i64)
+            }
+            _ => None,
+        };
+        match (integer, n.to_f64()) {
+            (Some(i), _) if i < 0 => error(
+                ErrorKind::WrongArguments,
+                "The factorial of a negative number is not defined".to_string(),
+            ),
+            (Some(i), _) => Parameters::from_big_int(fact(i)),
+            (None, Some(f)) => Parameters::Float(gamma(f + 1.0)),
+            (None, None) => incompatible_argument("fact"),
+        }
+    })
+}
+
+pub fn abs(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments("abs");
     }
 
     match p.get(0).unwrap() {
-        Parameters::Int(i) => Parameters::Int(fact(*i)),
-        Parameters::Float(f) => Parameters::Int(fact(*f as i64)),
+        Parameters::Int(i) => Parameters::Int(i.abs()),
+        Parameters::Float(f) => Parameters::Float(f.abs()),
+        Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
+        Parameters::Complex(c) => Parameters::Float(c.norm()),
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => factorial(&vec![t.clone()], ram),
+                None => unknown_variable(s),
+                Some(t) => abs(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("abs"),
     }
 }
+pub fn re(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments("re");
+    }
 
-pub fn abs(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    match p.get(0).unwrap() {
+        Parameters::Int(i) => Parameters::Int(*i),
+        Parameters::Float(f) => Parameters::Float(*f),
+        Parameters::Rational(s) => Parameters::Rational(s.clone()),
+        Parameters::Complex(c) => Parameters::Float(c.re),
+        Parameters::InterpreterVector(vec) => {
+            let mut res = Vec::new();
+            vec.clone()
+                .into_iter()
+                .for_each(|x| res.push(re(&vec![x], ram)));
+            Parameters::InterpreterVector(Box::from(res))
+        }
+        Parameters::Identifier(s) => match ram {
+            None => unknown_variable(s),
+            Some(ref t) => match t.get(s.as_str()) {
+                None => unknown_variable(s),
+                Some(t) => re(&vec![t.clone()], ram),
+            },
+        },
+        _ => incompatible_argument("re"),
+    }
+}
+
+pub fn float(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    distribution("float", "(x)", p, ram, 0, &[], |x, _| match x {
+        Parameters::Complex(c) => Parameters::Complex(*c),
+        x => match x.to_f64() {
+            Some(f) => Parameters::Float(f),
+            None => incompatible_argument("float"),
+        },
+    })
+}
+
+pub fn im(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("im");
     }
 
     match p.get(0).unwrap() {
-        Parameters::Int(i) => Parameters::Int(i.abs()),
-        Parameters::Float(f) => Parameters::Float(f.abs()),
-        Parameters::Rational(s) => Parameters::Rational(s.clone().abs()),
+        Parameters::Int(_) | Parameters::Float(_) | Parameters::Rational(_) => Parameters::Int(0),
+        Parameters::Complex(c) => Parameters::Float(c.im),
+        Parameters::InterpreterVector(vec) => {
+            let mut res = Vec::new();
+            vec.clone()
+                .into_iter()
+                .for_each(|x| res.push(im(&vec![x], ram)));
+            Parameters::InterpreterVector(Box::from(res))
+        }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
-                Some(t) => abs(&vec![t.clone()], ram),
+                None => unknown_variable(s),
+                Some(t) => im(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("im"),
+    }
+}
+
+pub fn arg(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments("arg");
+    }
+
+    match p.get(0).unwrap() {
+        x @ (Parameters::Int(_) | Parameters::Float(_) | Parameters::Rational(_)) => {
+            Parameters::Float(to_complex(x).unwrap().arg())
+        }
+        Parameters::Complex(c) => Parameters::Float(c.arg()),
+        Parameters::InterpreterVector(vec) => {
+            let mut res = Vec::new();
+            vec.clone()
+                .into_iter()
+                .for_each(|x| res.push(arg(&vec![x], ram)));
+            Parameters::InterpreterVector(Box::from(res))
+        }
+        Parameters::Identifier(s) => match ram {
+            None => unknown_variable(s),
+            Some(ref t) => match t.get(s.as_str()) {
+                None => unknown_variable(s),
+                Some(t) => arg(&vec![t.clone()], ram),
+            },
+        },
+        _ => incompatible_argument("arg"),
+    }
+}
+
+pub fn conj(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments("conj");
+    }
+
+    match p.get(0).unwrap() {
+        Parameters::Int(i) => Parameters::Int(*i),
+        Parameters::Float(f) => Parameters::Float(*f),
+        Parameters::Rational(s) => Parameters::Rational(s.clone()),
+        Parameters::Complex(c) => Parameters::Complex(c.conj()),
+        Parameters::InterpreterVector(vec) => {
+            let mut res = Vec::new();
+            vec.clone()
+                .into_iter()
+                .for_each(|x| res.push(conj(&vec![x], ram)));
+            Parameters::InterpreterVector(Box::from(res))
+        }
+        Parameters::Identifier(s) => match ram {
+            None => unknown_variable(s),
+            Some(ref t) => match t.get(s.as_str()) {
+                None => unknown_variable(s),
+                Some(t) => conj(&vec![t.clone()], ram),
+            },
+        },
+        _ => incompatible_argument("conj"),
     }
 }
 
 pub fn ceil(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("ceil");
     }
 
     match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Float((*i as f64).ceil()),
         Parameters::Float(f) => Parameters::Float(f.ceil()),
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => ceil(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("ceil"),
     }
 }
 
 pub fn floor(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("floor");
     }
 
     match p.get(0).unwrap() {
         Parameters::Int(i) => Parameters::Float((*i as f64).floor()),
         Parameters::Float(f) => Parameters::Float(f.floor()),
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => floor(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("floor"),
     }
 }
 
 pub fn round(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("round");
     }
 
     let mut plus = false;
@@ -1308,13 +1803,13 @@ pub fn round(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>
             }
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => round(&vec![t.clone(), Parameters::Float(sln)], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("round"),
     }
 }
 
@@ -1324,7 +1819,7 @@ pub fn norm(
     function: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("norm");
     }
 
     match p.get(0).unwrap() {
@@ -1346,13 +1841,13 @@ pub fn norm(
             }
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => norm(&vec![t.clone()], ram, function),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("norm"),
     }
 }
 
@@ -1361,7 +1856,7 @@ pub fn transpose_vectors(
     ram: &Option<&mut HashMap<String, Parameters>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("transpose_vector");
     }
 
     match p.get(0).unwrap() {
@@ -1382,13 +1877,13 @@ pub fn transpose_vectors(
             Parameters::InterpreterVector(Box::from(result))
         }
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => transpose_vectors(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("transpose_vector"),
     }
 }
 
@@ -1397,7 +1892,7 @@ pub fn transpose_matrices(
     ram: &Option<&mut HashMap<String, Parameters>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("transpose");
     }
 
     match p.get(0).unwrap() {
@@ -1430,13 +1925,13 @@ pub fn transpose_matrices(
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => transpose_matrices(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("transpose"),
     }
 }
 
@@ -1445,7 +1940,7 @@ pub fn det_matrix(
     ram: &Option<&mut HashMap<String, Parameters>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("det");
     }
 
     match p.get(0).unwrap() {
@@ -1485,13 +1980,13 @@ pub fn det_matrix(
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => det_matrix(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("det"),
     }
 }
 
@@ -1500,7 +1995,7 @@ pub fn inverse_matrix(
     ram: &Option<&mut HashMap<String, Parameters>>,
 ) -> Parameters {
     if p.len() < 1 {
-        return Parameters::Null;
+        return wrong_arguments("invert");
     }
 
     match p.get(0).unwrap() {
@@ -1531,7 +2026,10 @@ pub fn inverse_matrix(
             let r = lup_decompose(&mut res, &mut p, n, ram.as_deref());
 
             match r {
-                0 => Parameters::Null,
+                0 => error(
+                    ErrorKind::NotInvertible,
+                    "Determinant is zero, matrix is not invertible".to_string(),
+                ),
                 _ => {
                     let mut vec_ia = Vec::new();
                     for _ in 0..n {
@@ -1544,17 +2042,20 @@ pub fn inverse_matrix(
                     let det = lup_determinant(&mut res, &mut p, n, ram.as_deref());
                     match det {
                         Parameters::Int(0) => {
-                            return Parameters::Str(
+                            return error(
+                                ErrorKind::NotInvertible,
                                 "Determinant is zero, matrix is not invertible".to_string(),
                             )
                         }
                         Parameters::Float(s) if s.abs() < 1e-10 => {
-                            return Parameters::Str(
+                            return error(
+                                ErrorKind::NotInvertible,
                                 "Determinant is zero, matrix is not invertible".to_string(),
                             )
                         }
                         Parameters::Rational(s) if s.clone().is_null() => {
-                            return Parameters::Str(
+                            return error(
+                                ErrorKind::NotInvertible,
                                 "Determinant is zero, matrix is not invertible".to_string(),
                             )
                         }
@@ -1571,20 +2072,20 @@ pub fn inverse_matrix(
         }
 
         Parameters::Identifier(s) => match ram {
-            None => Parameters::Identifier("This variable is not initialized yet".to_string()),
+            None => unknown_variable(s),
             Some(ref t) => match t.get(s.as_str()) {
-                None => Parameters::Null,
+                None => unknown_variable(s),
                 Some(t) => inverse_matrix(&vec![t.clone()], ram),
             },
         },
-        _ => Parameters::Null,
+        _ => incompatible_argument("invert"),
     }
 }
 
 pub fn plot_fn(
     p: &Vec<Parameters>,
     ram: &Option<&mut HashMap<String, Parameters>>,
-    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
     terminal: bool,
 ) -> Parameters {
     let color = match load() {
@@ -1605,7 +2106,18 @@ pub fn plot_fn(
     let mut fun: bool = true;
     let mut first_vector = None;
     let mut second_vector = None;
+    let mut lambda = None;
     match fs {
+        Parameters::Lambda(names, body) => {
+            fd = "@lambda".to_string();
+            lambda = Some((
+                names
+                    .iter()
+                    .map(|n| Ast::new(Parameters::Identifier(n.to_string())))
+                    .collect::<Vec<Ast>>(),
+                *body.clone(),
+            ));
+        }
         Parameters::InterpreterVector(vec) => {
             fun = false;
             first_vector = Some(&**vec)
@@ -1870,10 +2382,15 @@ pub fn plot_fn(
                 }
             }
         }
+        if let Some(l) = lambda {
+            (vec, ast) = l;
+        }
 
         let mut sram: HashMap<String, Parameters> = HashMap::new();
         sram.insert("pi".to_string(), Parameters::Float(PI));
         sram.insert("e".to_string(), Parameters::Float(E));
+        sram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
+        sram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
         while start <= end {
             x.push(start);
             if &fd == "" {
@@ -1890,10 +2407,13 @@ pub fn plot_fn(
                     match v {
                         Ast::Nil => (),
                         Ast::Call { .. } => (),
+                        Ast::If { .. } => (),
+                        Ast::Index { .. } => (),
                         Ast::Node {
                             value: v,
                             left: _l,
                             right: _r,
+                            ..
                         } => match v {
                             Parameters::Identifier(s) => names.push(s.clone()),
                             _ => (),
@@ -1906,7 +2426,11 @@ pub fn plot_fn(
                     .for_each(|(name, param)| {
                         sram.insert(name.to_string(), param.clone());
                     });
-                y.push(match interpret(&ast, &mut sram, &mut HashMap::new()) {
+                let result = match functions {
+                    Some(ref mut t) => interpret(&ast, &mut sram, t),
+                    None => interpret(&ast, &mut sram, &mut HashMap::new()),
+                };
+                y.push(match result {
                     Parameters::Float(p) => p,
                     Parameters::Int(i) => i as f64,
                     Parameters::Rational(s) => s.approx(),
@@ -1975,6 +2499,13 @@ pub fn plot_fn(
             .lines_points(&x, &y, &[]),
         _ => f.axes2d().points(&x, &y, &[]),
     };
+    let (st, end) = match fun {
+        true => (st, end),
+        false => (
+            x.iter().cloned().fold(f64::INFINITY, f64::min),
+            x.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        ),
+    };
     if !terminal {
         f.show().unwrap();
     } else {
@@ -1982,3 +2513,2231 @@ pub fn plot_fn(
     }
     Parameters::Null
 }
+
+fn vector_argument(
+    p: &Parameters,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Option<Vec<Parameters>> {
+    match p {
+        Parameters::InterpreterVector(vec) => Some(
+            vec.iter()
+                .map(|x| match x {
+                    Parameters::Identifier(s) => match ram.as_ref().and_then(|r| r.get(s)) {
+                        Some(t) => t.clone(),
+                        None => x.clone(),
+                    },
+                    _ => x.clone(),
+                })
+                .collect(),
+        ),
+        Parameters::Identifier(s) => match ram.as_ref().and_then(|r| r.get(s)) {
+            Some(t) => vector_argument(&t.clone(), ram),
+            None => None,
+        },
+        _ => None,
+    }
+}
+
+fn matrix_argument(
+    p: &Parameters,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Option<Vec<Vec<Parameters>>> {
+    let rows: Option<Vec<Vec<Parameters>>> = vector_argument(p, ram)?
+        .iter()
+        .map(|row| match row {
+            Parameters::InterpreterVector(_) => vector_argument(row, ram),
+            _ => None,
+        })
+        .collect();
+    let rows = rows?;
+    let width = rows.first()?.len();
+    if width == 0
+        || rows
+            .iter()
+            .any(|r| r.len() != width || r.iter().any(|x| x.to_f64().is_none()))
+    {
+        return None;
+    }
+    Some(rows)
+}
+
+pub fn solve_linear(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    if p.len() != 2 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function solve_linear takes a matrix and a vector".to_string(),
+        );
+    }
+    let a = match matrix_argument(&p[0], ram) {
+        Some(a) => a,
+        None => return incompatible_argument("solve_linear"),
+    };
+    let (columns, is_vector) = match (matrix_argument(&p[1], ram), vector_argument(&p[1], ram)) {
+        (Some(b), _) => (transpose(b), false),
+        (None, Some(b)) if b.iter().all(|x| x.to_f64().is_some()) => (vec![b], true),
+        _ => return incompatible_argument("solve_linear"),
+    };
+    let (m, n) = (a.len(), a[0].len());
+    if columns[0].len() != m {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The right-hand side of solve_linear must have {m} rows"),
+        );
+    }
+    if m < n {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The system has {m} equations for {n} unknowns, it has no unique solution"),
+        );
+    }
+    let (mut a, columns) = if m > n {
+        normal_equations(&a, &columns, ram.as_deref())
+    } else {
+        (a, columns)
+    };
+
+    let mut permutation = vec![Parameters::Int(0); n + 1];
+    if lup_decompose(&mut a, &mut permutation, n, ram.as_deref()) == 0 {
+        return error(
+            ErrorKind::NotInvertible,
+            "The matrix is singular, the system has no unique solution".to_string(),
+        );
+    }
+    let solutions: Vec<Vec<Parameters>> = columns
+        .iter()
+        .map(|b| lup_solve(&a, &permutation, b, ram.as_deref()))
+        .collect();
+    if is_vector {
+        return Parameters::InterpreterVector(Box::from(solutions[0].clone()));
+    }
+    Parameters::InterpreterVector(Box::from(
+        transpose(solutions)
+            .into_iter()
+            .map(|row| Parameters::InterpreterVector(Box::from(row)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn matrix_value(rows: Vec<Vec<Parameters>>) -> Parameters {
+    Parameters::InterpreterVector(Box::from(
+        rows.into_iter()
+            .map(|row| Parameters::InterpreterVector(Box::from(row)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn float_matrix(rows: Vec<Vec<f64>>) -> Parameters {
+    let scale = rows
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |acc, x| acc.max(x.abs()));
+    matrix_value(
+        rows.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|x| match x {
+                        x if x.abs() <= 1e-14 * scale => Parameters::Float(0.0),
+                        x => Parameters::Float(x),
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+fn to_float_matrix(a: &[Vec<Parameters>]) -> Vec<Vec<f64>> {
+    a.iter()
+        .map(|row| row.iter().map(|x| x.to_f64().unwrap_or(f64::NAN)).collect())
+        .collect()
+}
+
+fn decomposition_argument(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+    square: bool,
+) -> Result<Vec<Vec<Parameters>>, Parameters> {
+    if p.len() != 1 {
+        return Err(wrong_arguments(name));
+    }
+    match matrix_argument(&p[0], ram) {
+        Some(a) if square && a.len() != a[0].len() => Err(error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} needs a square matrix"),
+        )),
+        Some(a) => Ok(a),
+        None => Err(incompatible_argument(name)),
+    }
+}
+
+pub fn trace_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    match decomposition_argument("trace", p, ram, true) {
+        Ok(a) => trace(&a, ram.as_deref()),
+        Err(e) => e,
+    }
+}
+
+pub fn rank_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    match decomposition_argument("rank", p, ram, false) {
+        Ok(mut a) => Parameters::Int(row_echelon(&mut a, ram.as_deref()).len() as i64),
+        Err(e) => e,
+    }
+}
+
+pub fn kernel_matrix(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    match decomposition_argument(name, p, ram, false) {
+        Ok(a) => matrix_value(kernel(&a, ram.as_deref())),
+        Err(e) => e,
+    }
+}
+
+pub fn lu_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let mut a = match decomposition_argument("lu", p, ram, true) {
+        Ok(a) => a,
+        Err(e) => return e,
+    };
+    let n = a.len();
+    let mut permutation = vec![Parameters::Int(0); n + 1];
+    lup_decompose(&mut a, &mut permutation, n, ram.as_deref());
+    let entry = |i: usize, j: usize| match i.cmp(&j) {
+        std::cmp::Ordering::Greater => a[i][j].clone(),
+        std::cmp::Ordering::Equal => Parameters::Int(1),
+        std::cmp::Ordering::Less => Parameters::Int(0),
+    };
+    let l = (0..n)
+        .map(|i| (0..n).map(|j| entry(i, j)).collect())
+        .collect();
+    let u = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| match j >= i {
+                    true => a[i][j].clone(),
+                    false => Parameters::Int(0),
+                })
+                .collect()
+        })
+        .collect();
+    let p = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| match permutation[i] {
+                    Parameters::Int(r) if r as usize == j => Parameters::Int(1),
+                    _ => Parameters::Int(0),
+                })
+                .collect()
+        })
+        .collect();
+    Parameters::InterpreterVector(Box::from(vec![
+        matrix_value(l),
+        matrix_value(u),
+        matrix_value(p),
+    ]))
+}
+
+pub fn qr_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    match decomposition_argument("qr", p, ram, false) {
+        Ok(a) => {
+            let (q, r) = householder_qr(&to_float_matrix(&a));
+            Parameters::InterpreterVector(Box::from(vec![float_matrix(q), float_matrix(r)]))
+        }
+        Err(e) => e,
+    }
+}
+
+pub fn svd_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    match decomposition_argument("svd", p, ram, false) {
+        Ok(a) => {
+            let (u, s, v) = jacobi_svd(&to_float_matrix(&a));
+            let s = s.into_iter().map(Parameters::Float).collect::<Vec<_>>();
+            Parameters::InterpreterVector(Box::from(vec![
+                float_matrix(u),
+                Parameters::InterpreterVector(Box::from(s)),
+                float_matrix(v),
+            ]))
+        }
+        Err(e) => e,
+    }
+}
+
+pub fn eig_matrix(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let a = match decomposition_argument("eig", p, ram, true) {
+        Ok(a) => a,
+        Err(e) => return e,
+    };
+    let polynomial = characteristic_polynomial(&a, ram.as_deref())
+        .into_iter()
+        .enumerate()
+        .fold(Polynomial::zero(), |acc, (k, c)| {
+            acc.add(&Polynomial::power("x", k as u32).scale(&c))
+        });
+    let mut values = Vec::new();
+    let mut vectors = Vec::new();
+    for (value, multiplicity) in polynomial.roots_with_multiplicity("x") {
+        let shifted: Vec<Vec<Parameters>> = a
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut row = row.clone();
+                row[i] = minus(row[i].clone(), value.clone(), ram.as_deref());
+                row
+            })
+            .collect();
+        let basis = kernel(&shifted, ram.as_deref());
+        if basis.len() < multiplicity as usize {
+            return error(
+                ErrorKind::NotInvertible,
+                format!(
+                    "The matrix is defective, the eigenvalue {value} lacks {} eigenvector(s)",
+                    multiplicity as usize - basis.len()
+                ),
+            );
+        }
+        for v in basis.into_iter().take(multiplicity as usize) {
+            values.push(value.clone());
+            vectors.push(Parameters::InterpreterVector(Box::from(v)));
+        }
+    }
+    Parameters::InterpreterVector(Box::from(vec![
+        Parameters::InterpreterVector(Box::from(values)),
+        Parameters::InterpreterVector(Box::from(vectors)),
+    ]))
+}
+
+fn apply_function(
+    f: &Parameters,
+    args: Vec<Parameters>,
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    match f {
+        Parameters::Lambda(names, body) => match functions {
+            Some(t) => call_lambda(names, body, args, t),
+            None => call_lambda(names, body, args, &mut HashMap::new()),
+        },
+        Parameters::Identifier(s) => exec(s.to_string(), args, ram, functions),
+        Parameters::Symbolic(e) if e.variables().len() == 1 => {
+            let names = e.variables().into_iter().collect();
+            match functions {
+                Some(t) => call_lambda(&names, &e.to_ast(), args, t),
+                None => call_lambda(&names, &e.to_ast(), args, &mut HashMap::new()),
+            }
+        }
+        _ => error(
+            ErrorKind::IncompatibleTypes,
+            format!("{f} is not a function"),
+        ),
+    }
+}
+
+fn higher_order_arguments(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Result<Vec<Vec<Parameters>>, Parameters> {
+    if p.len() < 2 {
+        return Err(error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} needs a function and a vector"),
+        ));
+    }
+    p[1..]
+        .iter()
+        .map(|x| vector_argument(x, ram).ok_or_else(|| incompatible_argument(name)))
+        .collect()
+}
+
+pub fn map(
+    p: &Vec<Parameters>,
+    mut ram: Option<&mut HashMap<String, Parameters>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    let vectors = match higher_order_arguments("map", p, &ram) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let len = vectors.iter().map(|v| v.len()).min().unwrap_or(0);
+    let mut res = Vec::new();
+    for i in 0..len {
+        let args = vectors.iter().map(|v| v[i].clone()).collect();
+        match apply_function(&p[0], args, ram.as_deref_mut(), functions.as_deref_mut()) {
+            e @ Parameters::Error(_) => return e,
+            x => res.push(x),
+        }
+    }
+    Parameters::InterpreterVector(Box::from(res))
+}
+
+pub fn filter(
+    p: &Vec<Parameters>,
+    mut ram: Option<&mut HashMap<String, Parameters>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    let vectors = match higher_order_arguments("filter", p, &ram) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mut res = Vec::new();
+    for x in vectors[0].iter() {
+        match apply_function(
+            &p[0],
+            vec![x.clone()],
+            ram.as_deref_mut(),
+            functions.as_deref_mut(),
+        ) {
+            Parameters::Bool(true) => res.push(x.clone()),
+            Parameters::Bool(false) => (),
+            e @ Parameters::Error(_) => return e,
+            _ => {
+                return error(
+                    ErrorKind::NotABoolean,
+                    "The function given to filter must return a boolean".to_string(),
+                )
+            }
+        }
+    }
+    Parameters::InterpreterVector(Box::from(res))
+}
+
+fn fold_vector(
+    f: &Parameters,
+    init: Parameters,
+    vec: &[Parameters],
+    mut ram: Option<&mut HashMap<String, Parameters>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    let mut acc = init;
+    for x in vec {
+        acc = apply_function(
+            f,
+            vec![acc, x.clone()],
+            ram.as_deref_mut(),
+            functions.as_deref_mut(),
+        );
+        if let Parameters::Error(_) = acc {
+            return acc;
+        }
+    }
+    acc
+}
+
+pub fn reduce(
+    p: &Vec<Parameters>,
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    let vectors = match higher_order_arguments("reduce", p, &ram) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match vectors[0].split_first() {
+        None => error(
+            ErrorKind::WrongArguments,
+            "The function reduce can not be applied to an empty vector".to_string(),
+        ),
+        Some((first, rest)) => fold_vector(&p[0], first.clone(), rest, ram, functions),
+    }
+}
+
+pub fn fold(
+    p: &Vec<Parameters>,
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    if p.len() < 3 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function fold needs a function, an initial value and a vector".to_string(),
+        );
+    }
+    let vec = match vector_argument(&p[2], &ram) {
+        Some(v) => v,
+        None => return incompatible_argument("fold"),
+    };
+    fold_vector(&p[0], p[1].clone(), &vec, ram, functions)
+}
+
+pub fn apply(
+    p: &Vec<Parameters>,
+    ram: Option<&mut HashMap<String, Parameters>>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    match p.split_first() {
+        Some((f, args)) => apply_function(f, args.to_vec(), ram, functions),
+        None => error(
+            ErrorKind::WrongArguments,
+            "The function apply needs a function".to_string(),
+        ),
+    }
+}
+
+pub fn zip(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments("zip");
+    }
+    let vectors = match p
+        .iter()
+        .map(|x| vector_argument(x, ram))
+        .collect::<Option<Vec<Vec<Parameters>>>>()
+    {
+        Some(v) => v,
+        None => return incompatible_argument("zip"),
+    };
+    let len = vectors.iter().map(|v| v.len()).min().unwrap_or(0);
+    let res: Vec<Parameters> = (0..len)
+        .map(|i| {
+            Parameters::InterpreterVector(Box::from(
+                vectors.iter().map(|v| v[i].clone()).collect::<Vec<_>>(),
+            ))
+        })
+        .collect();
+    Parameters::InterpreterVector(Box::from(res))
+}
+
+const MAX_RANGE_LENGTH: usize = 10_000_000;
+
+fn resolve_argument(p: &Parameters, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    match p {
+        Parameters::Identifier(s) => match ram.as_ref().and_then(|r| r.get(s)) {
+            Some(t) => t.clone(),
+            None => unknown_variable(s),
+        },
+        _ => p.clone(),
+    }
+}
+
+fn range_length(length: f64) -> Result<usize, Parameters> {
+    if length > MAX_RANGE_LENGTH as f64 {
+        return Err(error(
+            ErrorKind::WrongArguments,
+            format!("A range can not have more than {MAX_RANGE_LENGTH} elements"),
+        ));
+    }
+    Ok(length.max(0.0) as usize)
+}
+
+pub fn range(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 2 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function range needs a start and an end".to_string(),
+        );
+    }
+    let args: Vec<Parameters> = p.iter().take(3).map(|x| resolve_argument(x, ram)).collect();
+    if let Some(e) = args.iter().find_map(|x| x.first_error()) {
+        return Parameters::Error(e);
+    }
+
+    if let Some(exact) = args
+        .iter()
+        .map(|x| x.to_rational())
+        .collect::<Option<Vec<Rationals>>>()
+    {
+        let (start, end) = (exact[0].clone(), exact[1].clone());
+        let step = match exact.get(2) {
+            Some(s) => s.clone(),
+            None if end < start => Rationals::new(1, -1),
+            None => Rationals::new(1, 1),
+        };
+        if step.clone().is_null() {
+            return error(
+                ErrorKind::WrongArguments,
+                "The step of a range can not be zero".to_string(),
+            );
+        }
+        let count = ((end - start.clone()) / step.clone()).reduce();
+        let count: BigInt = count.over.div_floor(&count.under) + 1;
+        let length = match range_length(count.to_f64().unwrap_or(f64::INFINITY)) {
+            Ok(l) => l,
+            Err(e) => return e,
+        };
+        let mut res = Vec::with_capacity(length);
+        let mut current = start;
+        for _ in 0..length {
+            res.push(Parameters::from_rational(current.clone()));
+            current = current + step.clone();
+        }
+        return Parameters::InterpreterVector(Box::from(res));
+    }
+
+    let floats = match args
+        .iter()
+        .map(|x| x.to_f64())
+        .collect::<Option<Vec<f64>>>()
+    {
+        Some(f) => f,
+        None => return incompatible_argument("range"),
+    };
+    let (start, end) = (floats[0], floats[1]);
+    let step = match floats.get(2) {
+        Some(s) => *s,
+        None if end < start => -1.0,
+        None => 1.0,
+    };
+    if step == 0.0 || !step.is_finite() {
+        return error(
+            ErrorKind::WrongArguments,
+            "The step of a range can not be zero".to_string(),
+        );
+    }
+    let length = match range_length(((end - start) / step + 1e-9).floor() + 1.0) {
+        Ok(l) => l,
+        Err(e) => return e,
+    };
+    let res = (0..length)
+        .map(|k| Parameters::Float(start + (k as f64) * step))
+        .collect::<Vec<Parameters>>();
+    Parameters::InterpreterVector(Box::from(res))
+}
+
+pub fn linspace(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() < 3 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function linspace needs a start, an end and a number of points".to_string(),
+        );
+    }
+    let args: Vec<Parameters> = p.iter().take(3).map(|x| resolve_argument(x, ram)).collect();
+    if let Some(e) = args.iter().find_map(|x| x.first_error()) {
+        return Parameters::Error(e);
+    }
+    let n = match &args[2] {
+        Parameters::Int(n) if *n >= 1 => match range_length(*n as f64) {
+            Ok(n) => n,
+            Err(e) => return e,
+        },
+        _ => {
+            return error(
+                ErrorKind::WrongArguments,
+                "The number of points of linspace must be a positive integer".to_string(),
+            )
+        }
+    };
+    if n == 1 {
+        return Parameters::InterpreterVector(Box::from(vec![args[0].clone()]));
+    }
+
+    let res = match (args[0].to_rational(), args[1].to_rational()) {
+        (Some(start), Some(end)) => {
+            let step = (end - start.clone()) / Rationals::new(1, (n - 1) as i64);
+            (0..n)
+                .map(|k| {
+                    Parameters::from_rational(
+                        start.clone() + step.clone() * Rationals::new(1, k as i64),
+                    )
+                })
+                .collect::<Vec<Parameters>>()
+        }
+        _ => match (args[0].to_f64(), args[1].to_f64()) {
+            (Some(start), Some(end)) => {
+                let step = (end - start) / ((n - 1) as f64);
+                (0..n)
+                    .map(|k| Parameters::Float(start + (k as f64) * step))
+                    .collect::<Vec<Parameters>>()
+            }
+            _ => return incompatible_argument("linspace"),
+        },
+    };
+    Parameters::InterpreterVector(Box::from(res))
+}
+
+fn variable_name(ast: &Ast) -> Option<String> {
+    match ast {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+fn integer_bound(p: &Parameters) -> Option<i64> {
+    p.to_rational().and_then(|r| r.to_int())
+}
+
+fn big_integer(p: &Parameters) -> Option<BigInt> {
+    p.to_rational().and_then(|r| r.to_big_int())
+}
+
+fn series(
+    name: &str,
+    lst: &Vec<Ast>,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+    init: Parameters,
+    op: fn(Parameters, Parameters, Option<&HashMap<String, Parameters>>) -> Parameters,
+) -> Parameters {
+    let var = match variable_name(&lst[1]) {
+        Some(v) => v,
+        None => {
+            return error(
+                ErrorKind::WrongArguments,
+                format!("The second argument of {name} must be a variable name"),
+            )
+        }
+    };
+
+    let values: Vec<Parameters> = if lst.len() == 4 {
+        let from = interpret(&lst[2], ram, functions);
+        let to = interpret(&lst[3], ram, functions);
+        if let Some(e) = from.first_error().or(to.first_error()) {
+            return Parameters::Error(e);
+        }
+        let (from, to) = match (integer_bound(&from), integer_bound(&to)) {
+            (Some(f), Some(t)) => (f, t),
+            _ => {
+                return error(
+                    ErrorKind::WrongArguments,
+                    format!("The bounds of {name} must be integers"),
+                )
+            }
+        };
+        if to.saturating_sub(from) >= MAX_RANGE_LENGTH as i64 {
+            return error(
+                ErrorKind::WrongArguments,
+                format!("The function {name} can not have more than {MAX_RANGE_LENGTH} terms"),
+            );
+        }
+        (from..=to).map(Parameters::Int).collect()
+    } else {
+        let v = interpret(&lst[2], ram, functions);
+        if let Some(e) = v.first_error() {
+            return Parameters::Error(e);
+        }
+        match vector_argument(&v, &Some(ram)) {
+            Some(v) => v,
+            None => return incompatible_argument(name),
+        }
+    };
+
+    let mut scope = ram.clone();
+    let mut acc = init;
+    for value in values {
+        scope.insert(var.clone(), value);
+        let term = interpret(&lst[0], &mut scope, functions);
+        if term.first_error().is_some() {
+            return term;
+        }
+        acc = op(acc, term, Some(&scope));
+        if acc.first_error().is_some() {
+            return acc;
+        }
+    }
+    acc
+}
+
+fn function_to_differentiate(
+    ast: &Ast,
+    var: &Option<String>,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Option<(Vec<String>, Ast)> {
+    if let Some(name) = variable_name(ast) {
+        if let Some((params, body)) = functions.get(&name) {
+            let names = params.iter().filter_map(variable_name).collect();
+            return Some((names, body.clone()));
+        }
+        if let Some(Parameters::Lambda(params, body)) = ram.get(&name) {
+            return Some((params.clone(), *body.clone()));
+        }
+        return None;
+    }
+    if let Some(v) = var.as_ref().filter(|v| symbolic::depends_on(ast, v)) {
+        let params = vec![v.clone()];
+        let body = capture(ast, &params, ram);
+        if differentiate(&body, v, functions).is_ok() {
+            return Some((params, body));
+        }
+    }
+    match interpret(ast, ram, functions) {
+        Parameters::Lambda(params, body) => Some((params, *body)),
+        _ => None,
+    }
+}
+
+fn diff(
+    lst: &Vec<Ast>,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    let mut rest = &lst[1..];
+    let var = match rest.first().and_then(variable_name) {
+        Some(v) => {
+            rest = &rest[1..];
+            Some(v)
+        }
+        None => None,
+    };
+
+    let order = match rest {
+        [] => 1,
+        [o] => {
+            let o = interpret(o, ram, functions);
+            match integer_bound(&o) {
+                Some(o) if o >= 0 => o,
+                _ => {
+                    return error(
+                        ErrorKind::WrongArguments,
+                        "The order of diff must be a non-negative integer".to_string(),
+                    )
+                }
+            }
+        }
+        _ => {
+            return error(
+                ErrorKind::WrongArguments,
+                "The function diff takes a function, a variable and an order".to_string(),
+            )
+        }
+    };
+
+    let (params, body, var) = match (
+        function_to_differentiate(&lst[0], &var, ram, functions),
+        var,
+    ) {
+        (Some((params, body)), var) => match var.or(params.first().cloned()) {
+            Some(v) => (params, body, v),
+            None => {
+                return error(
+                    ErrorKind::WrongArguments,
+                    "A function without parameters can not be differentiated".to_string(),
+                )
+            }
+        },
+        (None, Some(v)) => {
+            let params = vec![v.clone()];
+            let body = capture(&lst[0], &params, ram);
+            (params, body, v)
+        }
+        (None, None) => {
+            return error(
+                ErrorKind::WrongArguments,
+                "diff needs a function or an expression and a variable".to_string(),
+            )
+        }
+    };
+
+    let mut derivative = body;
+    for _ in 0..order {
+        derivative = match differentiate(&derivative, &var, functions) {
+            Ok(d) => d,
+            Err(e) => return e,
+        };
+    }
+    Parameters::Lambda(params, Box::from(derivative))
+}
+
+fn transform(name: &str, e: Expression) -> Expression {
+    match name {
+        "factor" => e.factor(),
+        _ => e.expanded(),
+    }
+}
+
+fn normal_form(
+    name: &str,
+    p: &Vec<Parameters>,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    if p.len() < 1 {
+        return wrong_arguments(name);
+    }
+    match &p[0] {
+        Parameters::Symbolic(e) => transform(name, *e.clone()).into_parameters(),
+        Parameters::Lambda(params, body) => normal_form_body(name, params, body, functions),
+        Parameters::Identifier(s) => match functions.as_ref().and_then(|f| f.get(s)).cloned() {
+            Some((params, body)) => {
+                let names = params.iter().filter_map(variable_name).collect();
+                normal_form_body(name, &names, &body, functions)
+            }
+            None => transform(name, Expression::variable(s)).into_parameters(),
+        },
+        p if is_coefficient(p) && name == "factor" => error(
+            ErrorKind::WrongArguments,
+            "The function factor only takes integers, polynomials and functions".to_string(),
+        ),
+        p if is_coefficient(p) => p.clone(),
+        _ => incompatible_argument(name),
+    }
+}
+
+fn normal_form_body(
+    name: &str,
+    params: &Vec<String>,
+    body: &Ast,
+    functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    let mut empty = HashMap::new();
+    let functions = functions.unwrap_or(&mut empty);
+    let body = match interpret(body, &mut HashMap::new(), functions) {
+        Parameters::Symbolic(e) => transform(name, *e).to_ast(),
+        p if is_coefficient(&p) => Ast::new(p),
+        _ => symbolic::simplify(body),
+    };
+    Parameters::Lambda(params.clone(), Box::from(body))
+}
+
+const KRONROD_NODES: [f64; 8] = [
+    0.9914553711208126,
+    0.9491079123427585,
+    0.8648644233597691,
+    0.7415311855993945,
+    0.5860872354676911,
+    0.4058451513773972,
+    0.20778495500789848,
+    0.0,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022935322010529224,
+    0.06309209262997856,
+    0.10479001032225019,
+    0.14065325971552592,
+    0.1690047266392679,
+    0.19035057806478542,
+    0.20443294007529889,
+    0.20948214108472782,
+];
+
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.1294849661688697,
+    0.27970539148927664,
+    0.3818300505051189,
+    0.4179591836734694,
+];
+
+const DEFAULT_INTEGRATION_TOLERANCE: f64 = 1e-10;
+const MAX_SUBDIVISIONS: usize = 1000;
+
+fn gauss_kronrod(
+    f: &mut impl FnMut(f64) -> Result<f64, Parameters>,
+    a: f64,
+    b: f64,
+) -> Result<(f64, f64), Parameters> {
+    let (center, half) = ((a + b) / 2.0, (b - a) / 2.0);
+    let fc = f(center)?;
+    let mut kronrod = fc * KRONROD_WEIGHTS[7];
+    let mut gauss = fc * GAUSS_WEIGHTS[3];
+    for j in 0..7 {
+        let x = half * KRONROD_NODES[j];
+        let sum = f(center - x)? + f(center + x)?;
+        kronrod += KRONROD_WEIGHTS[j] * sum;
+        if j % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[j / 2] * sum;
+        }
+    }
+    Ok((kronrod * half, ((kronrod - gauss) * half).abs()))
+}
+
+fn adaptive_integral(
+    f: &mut impl FnMut(f64) -> Result<f64, Parameters>,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+) -> Result<(f64, f64), Parameters> {
+    let (value, err) = gauss_kronrod(f, a, b)?;
+    let mut intervals = vec![(a, b, value, err)];
+    let (mut value, mut err) = (value, err);
+    while err > tolerance.max(tolerance * value.abs()) && intervals.len() < MAX_SUBDIVISIONS {
+        let worst = intervals
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.3.total_cmp(&y.3))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let (a, b, v, e) = intervals.swap_remove(worst);
+        let middle = (a + b) / 2.0;
+        let (v1, e1) = gauss_kronrod(f, a, middle)?;
+        let (v2, e2) = gauss_kronrod(f, middle, b)?;
+        value += v1 + v2 - v;
+        err += e1 + e2 - e;
+        intervals.push((a, middle, v1, e1));
+        intervals.push((middle, b, v2, e2));
+    }
+    let value: f64 = intervals.iter().map(|x| x.2).sum();
+    let err: f64 = intervals.iter().map(|x| x.3).sum();
+    Ok((value, err))
+}
+
+pub fn integrate(
+    p: &Vec<Parameters>,
+    mut ram: Option<&mut HashMap<String, Parameters>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    if p.len() < 3 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function integrate takes a function and two bounds".to_string(),
+        );
+    }
+    let mut bounds = Vec::new();
+    for x in &p[1..] {
+        match resolve_argument(x, &ram).to_f64() {
+            Some(f) if f.is_finite() => bounds.push(f),
+            _ => return incompatible_argument("integrate"),
+        }
+    }
+    let (a, b) = (bounds[0], bounds[1]);
+    let tolerance = match bounds.get(2) {
+        Some(t) if *t > 0.0 => *t,
+        Some(_) => {
+            return error(
+                ErrorKind::WrongArguments,
+                "The tolerance of integrate must be positive".to_string(),
+            )
+        }
+        None => DEFAULT_INTEGRATION_TOLERANCE,
+    };
+
+    let mut f = |x: f64| {
+        let y = apply_function(
+            &p[0],
+            vec![Parameters::Float(x)],
+            ram.as_deref_mut(),
+            functions.as_deref_mut(),
+        );
+        match (y.to_f64(), y) {
+            (Some(y), _) => Ok(y),
+            (_, e @ Parameters::Error(_)) => Err(e),
+            (_, y) => Err(error(
+                ErrorKind::IncompatibleTypes,
+                format!("integrate needs a real valued function, got {y} at {x}"),
+            )),
+        }
+    };
+    let (value, err) = match adaptive_integral(&mut f, a.min(b), a.max(b), tolerance) {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+    let value = if a > b { -value } else { value };
+    Parameters::InterpreterVector(Box::from(vec![
+        Parameters::Float(value),
+        Parameters::Float(err),
+    ]))
+}
+
+const MAX_NEWTON_ITERATIONS: usize = 200;
+const MAX_BISECTIONS: usize = 2000;
+const MAX_EXACT_DENOMINATOR: i64 = 1_000_000;
+
+fn real_value(name: &str, y: Parameters, x: f64) -> Result<f64, Parameters> {
+    match (y.to_f64(), y) {
+        (Some(y), _) => Ok(y),
+        (_, e @ Parameters::Error(_)) => Err(e),
+        (_, y) => Err(error(
+            ErrorKind::IncompatibleTypes,
+            format!("{name} needs a real valued function, got {y} at {x}"),
+        )),
+    }
+}
+
+fn exact_root(f: &mut impl FnMut(Parameters) -> Parameters, x: f64) -> Parameters {
+    let r = Rationals::rationalize(x);
+    if r.under > BigInt::from(MAX_EXACT_DENOMINATOR) {
+        return Parameters::Float(x);
+    }
+    let root = Parameters::from_rational(r);
+    match f(root.clone()).to_f64() {
+        Some(0.0) => root,
+        _ => Parameters::Float(x),
+    }
+}
+
+fn newton(f: &mut impl FnMut(f64) -> Result<f64, Parameters>, x0: f64) -> Result<f64, Parameters> {
+    let mut x = x0;
+    let mut step = f64::INFINITY;
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let y = f(x)?;
+        if y == 0.0 || step.abs() <= 1e-15 * x.abs().max(1.0) {
+            return Ok(x);
+        }
+        let h = 1e-7 * x.abs().max(1.0);
+        let slope = (f(x + h)? - f(x - h)?) / (2.0 * h);
+        step = y / slope;
+        if !step.is_finite() || !(x - step).is_finite() {
+            return Err(error(
+                ErrorKind::WrongArguments,
+                format!("Newton's method stalled at {x}, try another starting point"),
+            ));
+        }
+        x -= step;
+    }
+    if step.abs() <= 1e-10 * x.abs().max(1.0) {
+        return Ok(x);
+    }
+    Err(error(
+        ErrorKind::WrongArguments,
+        format!("Newton's method did not converge from {x0}"),
+    ))
+}
+
+fn newton_root(
+    f: &Parameters,
+    x0: f64,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    let mut call = |x: Parameters| apply_function(f, vec![x], Some(ram), Some(functions));
+    let root = newton(
+        &mut |x: f64| real_value("solve", call(Parameters::Float(x)), x),
+        x0,
+    );
+    match root {
+        Ok(x) => exact_root(&mut call, x),
+        Err(e) => e,
+    }
+}
+
+fn is_equation(ast: &Ast) -> bool {
+    matches!(
+        ast,
+        Ast::Node {
+            value: Parameters::Equal,
+            ..
+        }
+    )
+}
+
+fn solve(
+    lst: &Vec<Ast>,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+) -> Parameters {
+    let var = match lst.get(1).and_then(variable_name) {
+        Some(v) if is_equation(&lst[0]) || !ram.contains_key(&v) => v,
+        _ => {
+            let f = interpret(&lst[0], ram, functions);
+            let x0 = interpret(&lst[1], ram, functions);
+            return match (f.first_error(), x0.to_f64()) {
+                (Some(e), _) => Parameters::Error(e),
+                (_, Some(x0)) if x0.is_finite() && lst.len() == 2 => {
+                    newton_root(&f, x0, ram, functions)
+                }
+                _ => incompatible_argument("solve"),
+            };
+        }
+    };
+
+    let variable = Ast::new(Parameters::Identifier(var.clone()));
+    let (lhs, rhs) = match &lst[0] {
+        Ast::Node {
+            value: Parameters::Equal,
+            left,
+            right,
+            ..
+        } => (*left.clone(), *right.clone()),
+        ast => {
+            match variable_name(ast).and(function_to_differentiate(ast, &None, ram, functions)) {
+                Some((params, body)) if params.len() == 1 => {
+                    let bindings = HashMap::from([(params[0].clone(), variable)]);
+                    (
+                        symbolic::substitute(&body, &bindings),
+                        Ast::new(Parameters::Int(0)),
+                    )
+                }
+                _ => (ast.clone(), Ast::new(Parameters::Int(0))),
+            }
+        }
+    };
+
+    let mut scope = ram.clone();
+    scope.remove(&var);
+    let l = interpret(&lhs, &mut scope, functions);
+    let r = interpret(&rhs, &mut scope, functions);
+    let difference = match (l.first_error(), r.first_error()) {
+        (None, None) => minus(l, r, Some(&scope)),
+        _ => Parameters::Null,
+    };
+    match difference {
+        Parameters::Symbolic(e) => {
+            let variables = e.variables();
+            if variables.len() > 1 || !variables.contains(&var) {
+                let others: Vec<String> = variables.into_iter().filter(|v| *v != var).collect();
+                return error(
+                    ErrorKind::WrongArguments,
+                    format!(
+                        "solve only handles equations in {var}, the equation also depends on {}",
+                        others.join(", ")
+                    ),
+                );
+            }
+            Parameters::InterpreterVector(Box::from(e.num.roots(&var)))
+        }
+        p if is_coefficient(&p) => match p.to_f64() {
+            Some(0.0) => error(
+                ErrorKind::WrongArguments,
+                format!("The equation holds for every value of {var}"),
+            ),
+            _ => Parameters::InterpreterVector(Box::from(vec![])),
+        },
+        _ => {
+            let x0 = match lst.get(2).map(|x| interpret(x, ram, functions)) {
+                None => 1.0,
+                Some(x) => match x.to_f64() {
+                    Some(x) if x.is_finite() => x,
+                    _ => return incompatible_argument("solve"),
+                },
+            };
+            let body = Ast::Node {
+                value: Parameters::MinusOperation,
+                left: Box::from(lhs),
+                right: Box::from(rhs),
+                position: Position::default(),
+            };
+            let params = vec![var];
+            let f = Parameters::Lambda(params.clone(), Box::from(capture(&body, &params, ram)));
+            newton_root(&f, x0, ram, functions)
+        }
+    }
+}
+
+pub fn bisect(
+    p: &Vec<Parameters>,
+    mut ram: Option<&mut HashMap<String, Parameters>>,
+    mut functions: Option<&mut HashMap<String, (Vec<Ast>, Ast)>>,
+) -> Parameters {
+    if p.len() < 3 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function bisect takes a function and two bounds".to_string(),
+        );
+    }
+    let mut bounds = Vec::new();
+    for x in &p[1..3] {
+        match resolve_argument(x, &ram).to_f64() {
+            Some(f) if f.is_finite() => bounds.push(f),
+            _ => return incompatible_argument("bisect"),
+        }
+    }
+    let (mut a, mut b) = (bounds[0].min(bounds[1]), bounds[0].max(bounds[1]));
+
+    let mut call = |x: Parameters| {
+        apply_function(&p[0], vec![x], ram.as_deref_mut(), functions.as_deref_mut())
+    };
+    let mut f = |x: f64| real_value("bisect", call(Parameters::Float(x)), x);
+    let (fa, fb) = match (f(a), f(b)) {
+        (Ok(fa), Ok(fb)) => (fa, fb),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    if fa == 0.0 || fb == 0.0 {
+        let x = if fa == 0.0 { a } else { b };
+        return exact_root(&mut call, x);
+    }
+    if fa.signum() == fb.signum() {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("bisect needs a sign change, but f({a}) and f({b}) have the same sign"),
+        );
+    }
+    for _ in 0..MAX_BISECTIONS {
+        let middle = (a + b) / 2.0;
+        if middle <= a || middle >= b {
+            break;
+        }
+        let fm = match f(middle) {
+            Ok(fm) => fm,
+            Err(e) => return e,
+        };
+        if fm == 0.0 {
+            a = middle;
+            b = middle;
+            break;
+        }
+        if fm.signum() == fa.signum() {
+            a = middle;
+        } else {
+            b = middle;
+        }
+    }
+    exact_root(&mut call, (a + b) / 2.0)
+}
+
+fn sample_argument(
+    p: &[Parameters],
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> (Vec<Vec<Parameters>>, bool) {
+    let resolved: Vec<Parameters> = p.iter().map(|x| resolve_argument(x, ram)).collect();
+    if let [single] = resolved.as_slice() {
+        if let Some(m) = matrix_argument(single, ram) {
+            return (transpose(m), true);
+        }
+        if let Some(v) = vector_argument(single, ram) {
+            return (vec![v], false);
+        }
+    }
+    (vec![resolved], false)
+}
+
+fn statistic(
+    name: &str,
+    p: &[Parameters],
+    ram: &Option<&mut HashMap<String, Parameters>>,
+    f: impl Fn(&[Parameters]) -> Parameters,
+) -> Parameters {
+    if p.is_empty() {
+        return wrong_arguments(name);
+    }
+    let (columns, matrix) = sample_argument(p, ram);
+    if columns.iter().flatten().any(|x| to_complex(x).is_none()) {
+        return incompatible_argument(name);
+    }
+    let results: Vec<Parameters> = columns.iter().map(|c| f(c)).collect();
+    if let Some(e) = results.iter().find(|x| matches!(x, Parameters::Error(_))) {
+        return e.clone();
+    }
+    if !matrix {
+        return results[0].clone();
+    }
+    let vectors: Option<Vec<Vec<Parameters>>> = results
+        .iter()
+        .map(|x| match x {
+            Parameters::InterpreterVector(v) => Some(v.to_vec()),
+            _ => None,
+        })
+        .collect();
+    match vectors {
+        Some(columns) if !columns.is_empty() => matrix_value(transpose(columns)),
+        _ => Parameters::InterpreterVector(Box::from(results)),
+    }
+}
+
+fn real_sample(name: &str, v: &[Parameters]) -> Result<Vec<(f64, Parameters)>, Parameters> {
+    if v.is_empty() {
+        return Err(error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} can not be applied to an empty sample"),
+        ));
+    }
+    v.iter()
+        .map(|x| match x {
+            Parameters::Complex(_) => Err(incompatible_argument(name)),
+            x => Ok((x.to_f64().unwrap_or(f64::NAN), x.clone())),
+        })
+        .collect()
+}
+
+fn sorted_sample(name: &str, v: &[Parameters]) -> Result<Vec<Parameters>, Parameters> {
+    let mut sample = real_sample(name, v)?;
+    sample.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(sample.into_iter().map(|x| x.1).collect())
+}
+
+fn total(v: &[Parameters]) -> Parameters {
+    v.iter()
+        .fold(Parameters::Int(0), |acc, x| other_add(acc, x.clone(), None))
+}
+
+fn cumulative_sum(v: &[Parameters]) -> Parameters {
+    let mut acc = Parameters::Int(0);
+    let sums = v
+        .iter()
+        .map(|x| {
+            acc = other_add(acc.clone(), x.clone(), None);
+            acc.clone()
+        })
+        .collect::<Vec<_>>();
+    Parameters::InterpreterVector(Box::from(sums))
+}
+
+fn mean_of(v: &[Parameters]) -> Parameters {
+    if v.is_empty() {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function mean can not be applied to an empty sample".to_string(),
+        );
+    }
+    divide(total(v), Parameters::Int(v.len() as i64), None)
+}
+
+fn median_of(v: &[Parameters]) -> Parameters {
+    let sorted = match sorted_sample("median", v) {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+    let n = sorted.len();
+    if n % 2 == 1 {
+        return sorted[n / 2].clone();
+    }
+    divide(
+        other_add(sorted[n / 2 - 1].clone(), sorted[n / 2].clone(), None),
+        Parameters::Int(2),
+        None,
+    )
+}
+
+fn mode_of(v: &[Parameters]) -> Parameters {
+    let mut sample = match real_sample("mode", v) {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+    sample.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut best = (0, 0);
+    let mut start = 0;
+    for i in 1..=sample.len() {
+        if i == sample.len() || sample[i].0 != sample[start].0 {
+            if i - start > best.1 {
+                best = (start, i - start);
+            }
+            start = i;
+        }
+    }
+    sample[best.0].1.clone()
+}
+
+fn extremum(name: &str, v: &[Parameters], largest: bool) -> Parameters {
+    let sample = match real_sample(name, v) {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+    let better = |a: f64, b: f64| if largest { a > b } else { a < b };
+    sample
+        .into_iter()
+        .reduce(|acc, x| if better(x.0, acc.0) { x } else { acc })
+        .map(|x| x.1)
+        .unwrap_or(Parameters::Null)
+}
+
+fn covariance_of(name: &str, x: &[Parameters], y: &[Parameters]) -> Parameters {
+    if x.len() != y.len() {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The samples of {name} must have the same length"),
+        );
+    }
+    if x.len() < 2 {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} needs at least two values"),
+        );
+    }
+    let (mx, my) = (mean_of(x), mean_of(y));
+    let products = x
+        .iter()
+        .zip(y)
+        .map(|(a, b)| {
+            mult(
+                minus(a.clone(), mx.clone(), None),
+                minus(b.clone(), my.clone(), None),
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    divide(total(&products), Parameters::Int(x.len() as i64 - 1), None)
+}
+
+fn variance_of(v: &[Parameters]) -> Parameters {
+    covariance_of("var", v, v)
+}
+
+fn standard_deviation_of(v: &[Parameters]) -> Parameters {
+    match variance_of(v) {
+        e @ Parameters::Error(_) => e,
+        var => Parameters::Float(var.to_f64().unwrap_or(f64::NAN).sqrt()),
+    }
+}
+
+fn correlation_of(x: &[Parameters], y: &[Parameters]) -> Parameters {
+    let values = [
+        covariance_of("corr", x, y),
+        covariance_of("corr", x, x),
+        covariance_of("corr", y, y),
+    ];
+    if let Some(e) = values.iter().find(|v| matches!(v, Parameters::Error(_))) {
+        return e.clone();
+    }
+    let [c, vx, vy] = values.map(|v| v.to_f64().unwrap_or(f64::NAN));
+    if vx == 0.0 || vy == 0.0 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The correlation of a constant sample is undefined".to_string(),
+        );
+    }
+    Parameters::Float(c / (vx * vy).sqrt())
+}
+
+fn quantile_of(v: &[Parameters], q: &Parameters) -> Parameters {
+    let sorted = match sorted_sample("quantile", v) {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+    match q.to_f64() {
+        Some(f) if (0.0..=1.0).contains(&f) => (),
+        _ => {
+            return error(
+                ErrorKind::WrongArguments,
+                "A quantile must be between 0 and 1".to_string(),
+            )
+        }
+    }
+    let h = mult(Parameters::Int(sorted.len() as i64 - 1), q.clone(), None);
+    let low = h.to_f64().unwrap_or(0.0).floor() as usize;
+    if low + 1 >= sorted.len() {
+        return sorted[low.min(sorted.len() - 1)].clone();
+    }
+    let fraction = minus(h, Parameters::Int(low as i64), None);
+    other_add(
+        sorted[low].clone(),
+        mult(
+            fraction,
+            minus(sorted[low + 1].clone(), sorted[low].clone(), None),
+            None,
+        ),
+        None,
+    )
+}
+
+pub fn quantile(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() != 2 {
+        return error(
+            ErrorKind::WrongArguments,
+            "The function quantile takes a sample and a quantile".to_string(),
+        );
+    }
+    let q = resolve_argument(&p[1], ram);
+    match vector_argument(&q, ram) {
+        Some(qs) => statistic("quantile", &p[..1], ram, |v| {
+            let values: Vec<Parameters> = qs.iter().map(|q| quantile_of(v, q)).collect();
+            match values.iter().find(|x| matches!(x, Parameters::Error(_))) {
+                Some(e) => e.clone(),
+                None => Parameters::InterpreterVector(Box::from(values)),
+            }
+        }),
+        None => statistic("quantile", &p[..1], ram, |v| quantile_of(v, &q)),
+    }
+}
+
+pub fn covariance(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let pair = |x: &[Parameters], y: &[Parameters]| match name {
+        "corr" => correlation_of(x, y),
+        _ => covariance_of(name, x, y),
+    };
+    match p.as_slice() {
+        [x, y] => match (vector_argument(x, ram), vector_argument(y, ram)) {
+            (Some(x), Some(y)) if x.iter().chain(&y).all(|v| v.to_f64().is_some()) => pair(&x, &y),
+            _ => incompatible_argument(name),
+        },
+        [m] => match matrix_argument(m, ram) {
+            Some(m) => {
+                let columns = transpose(m);
+                let rows: Vec<Vec<Parameters>> = columns
+                    .iter()
+                    .map(|x| columns.iter().map(|y| pair(x, y)).collect())
+                    .collect();
+                match rows
+                    .iter()
+                    .flatten()
+                    .find(|x| matches!(x, Parameters::Error(_)))
+                {
+                    Some(e) => e.clone(),
+                    None => matrix_value(rows),
+                }
+            }
+            None => incompatible_argument(name),
+        },
+        _ => wrong_arguments(name),
+    }
+}
+
+fn horner(coefficients: &[Parameters], x: &Parameters) -> Parameters {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Parameters::Int(0), |acc, c| {
+            other_add(mult(acc, x.clone(), None), c.clone(), None)
+        })
+}
+
+fn fit_polynomial(
+    name: &str,
+    x: &Parameters,
+    y: &Parameters,
+    degree: usize,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let (x, y) = match (vector_argument(x, ram), vector_argument(y, ram)) {
+        (Some(x), Some(y)) if x.iter().chain(&y).all(|v| v.to_f64().is_some()) => (x, y),
+        _ => return incompatible_argument(name),
+    };
+    if x.len() != y.len() {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The vectors given to {name} must have the same length"),
+        );
+    }
+    if x.len() <= degree {
+        return error(
+            ErrorKind::WrongArguments,
+            format!(
+                "{name} needs more than {degree} points to fit a polynomial of degree {degree}"
+            ),
+        );
+    }
+    let vandermonde: Vec<Vec<Parameters>> = x
+        .iter()
+        .map(|xi| {
+            let mut row = vec![Parameters::Int(1)];
+            for k in 1..=degree {
+                row.push(mult(row[k - 1].clone(), xi.clone(), None));
+            }
+            row
+        })
+        .collect();
+    let (mut a, b) = normal_equations(&vandermonde, std::slice::from_ref(&y), None);
+    let mut permutation = vec![Parameters::Int(0); degree + 2];
+    if lup_decompose(&mut a, &mut permutation, degree + 1, None) == 0 {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("{name} needs at least {} distinct x values", degree + 1),
+        );
+    }
+    let coefficients = lup_solve(&a, &permutation, &b[0], None);
+
+    let residuals: Vec<Parameters> = x
+        .iter()
+        .zip(&y)
+        .map(|(xi, yi)| {
+            let r = minus(yi.clone(), horner(&coefficients, xi), None);
+            mult(r.clone(), r, None)
+        })
+        .collect();
+    let my = mean_of(&y);
+    let deviations: Vec<Parameters> = y
+        .iter()
+        .map(|yi| {
+            let d = minus(yi.clone(), my.clone(), None);
+            mult(d.clone(), d, None)
+        })
+        .collect();
+    let (ss_res, ss_tot) = (total(&residuals), total(&deviations));
+    let r2 = match ss_tot.to_f64() {
+        Some(t) if t != 0.0 => minus(Parameters::Int(1), divide(ss_res, ss_tot, None), None),
+        _ => Parameters::Int(1),
+    };
+    Parameters::InterpreterVector(Box::from(vec![
+        Parameters::InterpreterVector(Box::from(coefficients)),
+        r2,
+    ]))
+}
+
+pub fn linreg(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    match p.as_slice() {
+        [x, y] => fit_polynomial("linreg", x, y, 1, ram),
+        _ => error(
+            ErrorKind::WrongArguments,
+            "The function linreg takes a vector of x and a vector of y".to_string(),
+        ),
+    }
+}
+
+pub fn polyfit(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    match p.as_slice() {
+        [x, y, d] => match resolve_argument(d, ram) {
+            Parameters::Int(d) if d >= 0 => fit_polynomial("polyfit", x, y, d as usize, ram),
+            _ => error(
+                ErrorKind::WrongArguments,
+                "The degree of polyfit must be a non-negative integer".to_string(),
+            ),
+        },
+        _ => error(
+            ErrorKind::WrongArguments,
+            "The function polyfit takes a vector of x, a vector of y and a degree".to_string(),
+        ),
+    }
+}
+
+pub fn poly(p: &Vec<Parameters>, ram: &Option<&mut HashMap<String, Parameters>>) -> Parameters {
+    if p.len() != 1 {
+        return wrong_arguments("poly");
+    }
+    let coefficients = match vector_argument(&p[0], ram) {
+        Some(v) => match v.first() {
+            Some(Parameters::InterpreterVector(fit)) => fit.to_vec(),
+            _ => v,
+        },
+        None => return incompatible_argument("poly"),
+    };
+    if coefficients.is_empty() || !coefficients.iter().all(is_coefficient) {
+        return incompatible_argument("poly");
+    }
+    let polynomial = coefficients
+        .iter()
+        .enumerate()
+        .fold(Polynomial::zero(), |acc, (k, c)| {
+            acc.add(&Polynomial::power("x", k as u32).scale(c))
+        });
+    Parameters::Lambda(
+        vec!["x".to_string()],
+        Box::from(Expression::from_polynomial(polynomial).to_ast()),
+    )
+}
+
+const MAX_EXACT_BINOMIAL: i64 = 1000;
+
+fn distribution(
+    name: &str,
+    usage: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+    required: usize,
+    defaults: &[Parameters],
+    f: impl Fn(&Parameters, &[Parameters]) -> Parameters,
+) -> Parameters {
+    let extra = p.len().saturating_sub(1);
+    if p.is_empty() || extra < required || extra > required + defaults.len() {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} takes the arguments {usage}"),
+        );
+    }
+    let mut args: Vec<Parameters> = p[1..].iter().map(|x| resolve_argument(x, ram)).collect();
+    args.extend_from_slice(&defaults[extra - required..]);
+    if args.iter().any(|x| x.to_f64().is_none()) {
+        return incompatible_argument(name);
+    }
+    let x = resolve_argument(&p[0], ram);
+    match vector_argument(&x, ram) {
+        Some(v) => {
+            let values: Vec<Parameters> = v.iter().map(|x| f(x, &args)).collect();
+            match values.iter().find(|x| matches!(x, Parameters::Error(_))) {
+                Some(e) => e.clone(),
+                None => Parameters::InterpreterVector(Box::from(values)),
+            }
+        }
+        None => f(&x, &args),
+    }
+}
+
+fn float_distribution(
+    name: &str,
+    usage: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+    required: usize,
+    defaults: &[f64],
+    f: impl Fn(f64, &[f64]) -> Result<f64, String>,
+) -> Parameters {
+    let defaults: Vec<Parameters> = defaults.iter().map(|d| Parameters::Float(*d)).collect();
+    distribution(name, usage, p, ram, required, &defaults, |x, args| {
+        let args: Vec<f64> = args.iter().filter_map(|a| a.to_f64()).collect();
+        match x.to_f64() {
+            Some(x) => match f(x, &args) {
+                Ok(v) => Parameters::Float(v),
+                Err(m) => error(ErrorKind::WrongArguments, m),
+            },
+            None => incompatible_argument(name),
+        }
+    })
+}
+
+fn check_positive(name: &str, what: &str, v: f64) -> Result<(), String> {
+    match v > 0.0 {
+        true => Ok(()),
+        false => Err(format!("The {what} of {name} must be positive")),
+    }
+}
+
+fn check_probability(name: &str, p: f64) -> Result<(), String> {
+    match (0.0..=1.0).contains(&p) {
+        true => Ok(()),
+        false => Err(format!(
+            "The probability given to {name} must be between 0 and 1"
+        )),
+    }
+}
+
+fn invert_cdf(cdf: impl Fn(f64) -> f64, p: f64, low: f64, high: f64, lowest: f64) -> f64 {
+    match p {
+        0.0 => lowest,
+        1.0 => f64::INFINITY,
+        p => invert_increasing(cdf, p, low, high),
+    }
+}
+
+fn normal(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let usage = match name {
+        "norminv" => "(p, mu = 0, sigma = 1)",
+        _ => "(x, mu = 0, sigma = 1)",
+    };
+    float_distribution(name, usage, p, ram, 0, &[0.0, 1.0], |x, args| {
+        let (mu, sigma) = (args[0], args[1]);
+        check_positive(name, "standard deviation", sigma)?;
+        let cdf = |z: f64| 0.5 * erfc(-z / std::f64::consts::SQRT_2);
+        Ok(match name {
+            "normpdf" => {
+                let z = (x - mu) / sigma;
+                (-z * z / 2.0).exp() / (sigma * (2.0 * PI).sqrt())
+            }
+            "normcdf" => cdf((x - mu) / sigma),
+            _ => {
+                check_probability(name, x)?;
+                mu + sigma * invert_cdf(cdf, x, -1.0, 1.0, f64::NEG_INFINITY)
+            }
+        })
+    })
+}
+
+fn exponential(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let usage = match name {
+        "expinv" => "(p, lambda = 1)",
+        _ => "(x, lambda = 1)",
+    };
+    float_distribution(name, usage, p, ram, 0, &[1.0], |x, args| {
+        let rate = args[0];
+        check_positive(name, "rate", rate)?;
+        Ok(match name {
+            "exppdf" if x < 0.0 => 0.0,
+            "exppdf" => rate * (-rate * x).exp(),
+            "expcdf" if x < 0.0 => 0.0,
+            "expcdf" => -(-rate * x).exp_m1(),
+            _ => {
+                check_probability(name, x)?;
+                -(-x).ln_1p() / rate
+            }
+        })
+    })
+}
+
+fn student_cdf(t: f64, nu: f64) -> f64 {
+    let tail = 0.5 * beta_regularized(nu / 2.0, 0.5, nu / (nu + t * t));
+    if t > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+fn student(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let usage = match name {
+        "tinv" => "(p, nu)",
+        _ => "(x, nu)",
+    };
+    float_distribution(name, usage, p, ram, 1, &[], |x, args| {
+        let nu = args[0];
+        check_positive(name, "number of degrees of freedom", nu)?;
+        Ok(match name {
+            "tpdf" => (ln_gamma((nu + 1.0) / 2.0)
+                - ln_gamma(nu / 2.0)
+                - 0.5 * (nu * PI).ln()
+                - (nu + 1.0) / 2.0 * (1.0 + x * x / nu).ln())
+            .exp(),
+            "tcdf" => student_cdf(x, nu),
+            _ => {
+                check_probability(name, x)?;
+                invert_cdf(|t| student_cdf(t, nu), x, -1.0, 1.0, f64::NEG_INFINITY)
+            }
+        })
+    })
+}
+
+fn chi_squared(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let usage = match name {
+        "chi2inv" => "(p, k)",
+        _ => "(x, k)",
+    };
+    float_distribution(name, usage, p, ram, 1, &[], |x, args| {
+        let k = args[0];
+        check_positive(name, "number of degrees of freedom", k)?;
+        Ok(match name {
+            "chi2pdf" if x < 0.0 => 0.0,
+            "chi2pdf" if x == 0.0 => match k {
+                k if k < 2.0 => f64::INFINITY,
+                2.0 => 0.5,
+                _ => 0.0,
+            },
+            "chi2pdf" => {
+                ((k / 2.0 - 1.0) * x.ln() - x / 2.0 - k / 2.0 * 2.0_f64.ln() - ln_gamma(k / 2.0))
+                    .exp()
+            }
+            "chi2cdf" if x <= 0.0 => 0.0,
+            "chi2cdf" => gamma_p(k / 2.0, x / 2.0),
+            _ => {
+                check_probability(name, x)?;
+                invert_cdf(
+                    |y| gamma_p(k / 2.0, y.max(0.0) / 2.0),
+                    x,
+                    0.0,
+                    k.max(1.0),
+                    0.0,
+                )
+            }
+        })
+    })
+}
+
+fn poisson(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    float_distribution(name, "(k, lambda)", p, ram, 1, &[], |k, args| {
+        let lambda = args[0];
+        check_positive(name, "mean", lambda)?;
+        Ok(match name {
+            "poissonpdf" if k < 0.0 || k != k.floor() => 0.0,
+            "poissonpdf" => (k * lambda.ln() - lambda - ln_gamma(k + 1.0)).exp(),
+            _ if k < 0.0 => 0.0,
+            _ => gamma_q(k.floor() + 1.0, lambda),
+        })
+    })
+}
+
+fn binomial_terms(n: i64, k: i64, p: &Parameters) -> Vec<Parameters> {
+    let q = minus(Parameters::Int(1), p.clone(), None);
+    let mut term = (0..n).fold(Parameters::Int(1), |acc, _| mult(acc, q.clone(), None));
+    let ratio = divide(p.clone(), q, None);
+    let mut terms = vec![term.clone()];
+    for i in 0..k {
+        term = mult(
+            term,
+            mult(
+                ratio.clone(),
+                Parameters::Rational(Rationals::new(i + 1, n - i)),
+                None,
+            ),
+            None,
+        );
+        terms.push(term.clone());
+    }
+    terms
+}
+
+fn binomial(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    distribution(name, "(k, n, p)", p, ram, 2, &[], |k, args| {
+        let (n, prob) = (&args[0], &args[1]);
+        let n = match n {
+            Parameters::Int(n) if *n >= 0 => *n,
+            _ => {
+                return error(
+                    ErrorKind::WrongArguments,
+                    format!("The number of trials of {name} must be a non-negative integer"),
+                )
+            }
+        };
+        let pf = prob.to_f64().unwrap_or(f64::NAN);
+        if let Err(m) = check_probability(name, pf) {
+            return error(ErrorKind::WrongArguments, m);
+        }
+        let kf = match k.to_f64() {
+            Some(k) => k,
+            None => return incompatible_argument(name),
+        };
+        let cdf = name == "binomcdf";
+        if kf < 0.0 || (!cdf && kf != kf.floor()) {
+            return Parameters::Int(0);
+        }
+        if (!cdf && kf > n as f64) || (cdf && kf >= n as f64) {
+            return Parameters::Int(if cdf { 1 } else { 0 });
+        }
+        let k = kf.floor() as i64;
+        if pf == 0.0 || pf == 1.0 {
+            let certain = if pf == 0.0 { 0 } else { n };
+            let hit = if cdf { k >= certain } else { k == certain };
+            return Parameters::Int(hit as i64);
+        }
+        if matches!(prob, Parameters::Int(_) | Parameters::Rational(_)) && n <= MAX_EXACT_BINOMIAL {
+            let terms = binomial_terms(n, k, prob);
+            return match cdf {
+                true => total(&terms),
+                false => terms[k as usize].clone(),
+            };
+        }
+        let (n, k) = (n as f64, k as f64);
+        Parameters::Float(match cdf {
+            true => beta_regularized(n - k, k + 1.0, 1.0 - pf),
+            false => (ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0)
+                + k * pf.ln()
+                + (n - k) * (1.0 - pf).ln())
+            .exp(),
+        })
+    })
+}
+
+fn non_positive_integer(x: f64) -> bool {
+    x <= 0.0 && x == x.floor()
+}
+
+fn special_function(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    distribution(name, "(x)", p, ram, 0, &[], |x, _| {
+        let f = match x.to_f64() {
+            Some(f) => f,
+            None => return incompatible_argument(name),
+        };
+        match (name, x) {
+            ("gamma" | "lgamma", _) if non_positive_integer(f) => error(
+                ErrorKind::WrongArguments,
+                format!("The function {name} is not defined for non-positive integers"),
+            ),
+            ("gamma", Parameters::Int(n)) => Parameters::from_big_int(fact(n - 1)),
+            ("gamma", _) => Parameters::Float(gamma(f)),
+            ("lgamma", _) => Parameters::Float(ln_gamma(f)),
+            ("erf", _) => Parameters::Float(erf(f)),
+            ("erfc", _) => Parameters::Float(erfc(f)),
+            ("zeta", _) if f == 1.0 => error(
+                ErrorKind::WrongArguments,
+                "The function zeta has a pole at 1".to_string(),
+            ),
+            _ => Parameters::Float(zeta(f)),
+        }
+    })
+}
+
+fn beta_function(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    distribution("beta", "(a, b)", p, ram, 1, &[], |a, args| {
+        match (a, &args[0]) {
+            (Parameters::Int(a), Parameters::Int(b)) if *a > 0 && *b > 0 => {
+                Parameters::from_rational(Rationals::from_big(
+                    fact(a + b - 1),
+                    fact(a - 1) * fact(b - 1),
+                ))
+            }
+            (a, b) => match (a.to_f64(), b.to_f64()) {
+                (Some(a), Some(b))
+                    if non_positive_integer(a)
+                        || non_positive_integer(b)
+                        || non_positive_integer(a + b) =>
+                {
+                    error(
+                        ErrorKind::WrongArguments,
+                        "The function beta is not defined for non-positive integers".to_string(),
+                    )
+                }
+                (Some(a), Some(b)) => Parameters::Float(beta(a, b)),
+                _ => incompatible_argument("beta"),
+            },
+        }
+    })
+}
+
+fn bessel(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    if p.len() != 2 {
+        return error(
+            ErrorKind::WrongArguments,
+            format!("The function {name} takes the arguments (n, x)"),
+        );
+    }
+    let n = match resolve_argument(&p[0], ram).to_f64() {
+        Some(n) if n == n.floor() && n.abs() <= i32::MAX as f64 => n as i64,
+        _ => {
+            return error(
+                ErrorKind::WrongArguments,
+                format!("The order of {name} must be an integer"),
+            )
+        }
+    };
+    let arguments = vec![p[1].clone(), Parameters::Int(n)];
+    float_distribution(name, "(n, x)", &arguments, ram, 1, &[], |x, _| match name {
+        "besselj" => Ok(bessel_j(n, x)),
+        _ if x <= 0.0 => Err("The argument of bessely must be positive".to_string()),
+        _ => Ok(bessel_y(n, x)),
+    })
+}
+
+fn is_integer_argument(
+    p: Option<&Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> bool {
+    match p.map(|x| resolve_argument(x, ram)) {
+        Some(x) if big_integer(&x).is_some() => true,
+        Some(x) => vector_argument(&x, ram)
+            .is_some_and(|v| !v.is_empty() && v.iter().all(|x| big_integer(x).is_some())),
+        None => false,
+    }
+}
+
+fn integer_function(
+    name: &str,
+    usage: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+    required: usize,
+    f: impl Fn(&BigInt, &[BigInt]) -> Result<Parameters, String>,
+) -> Parameters {
+    distribution(name, usage, p, ram, required, &[], |n, args| {
+        let args: Vec<BigInt> = args.iter().filter_map(big_integer).collect();
+        match big_integer(n) {
+            Some(n) if args.len() == required => match f(&n, &args) {
+                Ok(v) => v,
+                Err(m) => error(ErrorKind::WrongArguments, m),
+            },
+            _ => error(
+                ErrorKind::WrongArguments,
+                format!("The function {name} only takes integers"),
+            ),
+        }
+    })
+}
+
+fn prime_factors(
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    integer_function("factor", "(n)", p, ram, 0, |n, _| {
+        if n.is_zero() {
+            return Err("The function factor is not defined for 0".to_string());
+        }
+        let sign = match n.is_negative() {
+            true => vec![vec![Parameters::Int(-1), Parameters::Int(1)]],
+            false => vec![],
+        };
+        let factors = factorize(n)
+            .ok_or_else(|| format!("The number {n} is too large to be factored"))?
+            .into_iter()
+            .map(|(p, k)| vec![Parameters::from_big_int(p), Parameters::Int(k as i64)]);
+        Ok(matrix_value(sign.into_iter().chain(factors).collect()))
+    })
+}
+
+fn common_divisor(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let mut values = vec![];
+    for x in p.iter().map(|x| resolve_argument(x, ram)) {
+        match vector_argument(&x, ram) {
+            Some(v) => values.extend(v),
+            None => values.push(x),
+        }
+    }
+    let mut integers = vec![];
+    for x in values {
+        match (big_integer(&x), x) {
+            (Some(i), _) => integers.push(i),
+            (None, e @ Parameters::Error(_)) => return e,
+            _ => {
+                return error(
+                    ErrorKind::WrongArguments,
+                    format!("The function {name} only takes integers"),
+                )
+            }
+        }
+    }
+    match (name, integers.is_empty()) {
+        (_, true) => wrong_arguments(name),
+        ("gcd", _) => {
+            Parameters::from_big_int(integers.iter().fold(BigInt::from(0), |acc, i| acc.gcd(i)))
+        }
+        _ => Parameters::from_big_int(integers.iter().fold(BigInt::from(1), |acc, i| lcm(&acc, i))),
+    }
+}
+
+fn arrangements(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    integer_function(name, "(n, k)", p, ram, 1, |n, args| {
+        let (n, k) = match (n.to_i64(), args[0].to_i64()) {
+            (Some(n), Some(k)) => (n, k),
+            _ => return Err(format!("The arguments of {name} are too large")),
+        };
+        if n < 0 {
+            return Err(format!("The function {name} needs a non-negative n"));
+        }
+        Ok(Parameters::from_big_int(match name {
+            "nPr" => permutations(n, k),
+            _ => combinations(n, k),
+        }))
+    })
+}
+
+fn prime_function(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    integer_function(name, "(n)", p, ram, 0, |n, _| match name {
+        "isprime" => Ok(Parameters::Bool(is_prime(n))),
+        "nextprime" => Ok(Parameters::from_big_int(next_prime(n))),
+        _ if !n.is_positive() => Err("The function totient needs a positive integer".to_string()),
+        _ => totient(n)
+            .map(Parameters::from_big_int)
+            .ok_or_else(|| format!("The number {n} is too large to be factored")),
+    })
+}
+
+fn modular(
+    name: &str,
+    p: &Vec<Parameters>,
+    ram: &Option<&mut HashMap<String, Parameters>>,
+) -> Parameters {
+    let (usage, required) = match name {
+        "modpow" => ("(b, e, m)", 2),
+        _ => ("(a, m)", 1),
+    };
+    integer_function(name, usage, p, ram, required, |a, args| {
+        let m = &args[required - 1];
+        match name {
+            "mod" if m.is_zero() => Err("The modulus of mod can not be 0".to_string()),
+            "mod" => Ok(Parameters::from_big_int(a.mod_floor(&m.abs()))),
+            _ if !m.is_positive() => Err(format!("The modulus of {name} must be positive")),
+            "modinv" => mod_inverse(a, m)
+                .map(Parameters::from_big_int)
+                .ok_or_else(|| format!("{a} has no inverse modulo {m}")),
+            _ => mod_pow(a, &args[0], m)
+                .map(Parameters::from_big_int)
+                .ok_or_else(|| format!("{a} has no inverse modulo {m}")),
+        }
+    })
+}
@@ -1,8 +1,10 @@
 use std::str::FromStr;
 
+use num_bigint::BigInt;
+
 use crate::lexing::token::Operator::*;
 use crate::lexing::token::Token;
-use crate::lexing::token::Token::PreAnd;
+use crate::lexing::token::Token::{PreAnd, PreOr};
 
 pub fn is_an_allowed_char(character: char) -> bool {
     character.is_alphanumeric()
@@ -24,9 +26,12 @@ pub fn is_an_allowed_char(character: char) -> bool {
         || character == '&'
         || character == '['
         || character == ']'
+        || character == '?'
+        || character == ':'
         || character == '_'
+        || character == '$'
         || character == '"'
-        || character == ' '
+        || character.is_whitespace()
 }
 
 fn lex_int(
@@ -34,13 +39,17 @@ fn lex_int(
     chars: &mut Vec<char>,
     current_pos: usize,
     len: usize,
-) -> (i64, usize) {
-    let (a, b) = lex_raddix(current_char, chars, current_pos, len);
-    let err = i64::from_str(&a);
-    if err.is_err() {
-        (0, b)
-    } else {
-        (err.unwrap(), b)
+) -> (String, usize) {
+    lex_raddix(current_char, chars, current_pos, len)
+}
+
+fn int_token(digits: &str) -> Token {
+    match i64::from_str(digits) {
+        Ok(i) => Token::INT(i),
+        Err(_) => match BigInt::from_str(digits) {
+            Ok(i) => Token::BigInteger(i),
+            Err(_) => Token::FLOAT(f64::NAN),
+        },
     }
 }
 
@@ -71,7 +80,11 @@ fn lex_string(
     len: usize,
 ) -> (String, usize) {
     let mut str: String = String::new();
-    while current_pos < len && (current_char.is_alphanumeric() || current_char == '_') {
+    while current_pos < len
+        && (current_char.is_alphanumeric()
+            || current_char == '_'
+            || (current_char == '$' && str.is_empty()))
+    {
         str += &*current_char.to_string();
 
         current_pos += 1;
@@ -85,7 +98,7 @@ fn lex_string(
 }
 
 fn lex_float(
-    whole_side: i64,
+    whole_side: &str,
     chars: &mut Vec<char>,
     mut current_pos: usize,
     len: usize,
@@ -97,7 +110,7 @@ fn lex_float(
         None => &'0',
     };
     let (a, b) = lex_raddix(*current_char, chars, current_pos, len);
-    let f = f64::from_str(&*(whole_side.to_string().as_str().to_owned() + "." + a.as_str()));
+    let f = f64::from_str(&*(whole_side.to_owned() + "." + a.as_str()));
     if f.is_err() {
         return (f64::NAN, b);
     }
@@ -105,7 +118,14 @@ fn lex_float(
 }
 
 pub fn lex(input: String) -> Vec<Token> {
-    let mut vec: Vec<Token> = Vec::new();
+    lex_with_positions(input)
+        .into_iter()
+        .map(|(token, _)| token)
+        .collect()
+}
+
+pub fn lex_with_positions(input: String) -> Vec<(Token, usize)> {
+    let mut vec: Vec<(Token, usize)> = Vec::new();
 
     let mut current_pos = 0;
 
@@ -113,196 +133,232 @@ pub fn lex(input: String) -> Vec<Token> {
 
     let mut quote_i = 0;
 
-    let length = input.len();
-    while current_pos < input.len() {
+    let length = chars.len();
+    while current_pos < length {
         let current_character: char = chars.get(current_pos).unwrap().to_ascii_lowercase();
+        let start = current_pos;
         if !is_an_allowed_char(current_character) {
+            vec.push((Token::ILLEGAL(chars[current_pos]), start));
             current_pos += 1;
             continue;
         };
 
         match current_character {
             '+' => {
-                vec.push(Token::OPE(PLUS));
+                vec.push((Token::OPE(PLUS), start));
                 current_pos += 1
             }
             '-' => {
-                vec.push(Token::OPE(MINUS));
+                vec.push((Token::OPE(MINUS), start));
                 current_pos += 1
             }
             '*' => {
-                vec.push(Token::OPE(MULTIPLICATION));
+                vec.push((Token::OPE(MULTIPLICATION), start));
                 current_pos += 1
             }
             '/' => {
-                vec.push(Token::OPE(DIVIDE));
+                vec.push((Token::OPE(DIVIDE), start));
                 current_pos += 1
             }
             ')' => {
-                vec.push(Token::RPAR);
+                vec.push((Token::RPAR, start));
                 current_pos += 1
             }
             '(' => {
-                vec.push(Token::LPAR);
+                vec.push((Token::LPAR, start));
                 current_pos += 1
             }
             '>' => {
-                vec.push(Token::OPE(GreaterThan));
+                match vec.last() {
+                    Some((Token::OPE(MINUS), position)) if position + 1 == start => {
+                        let position = *position;
+                        vec.pop();
+                        vec.push((Token::ARROW, position))
+                    }
+                    _ => vec.push((Token::OPE(GreaterThan), start)),
+                }
                 current_pos += 1
             }
             '<' => {
-                vec.push(Token::OPE(LesserThan));
+                vec.push((Token::OPE(LesserThan), start));
                 current_pos += 1
             }
             '"' => {
-                vec.push(Token::QUOTE);
+                vec.push((Token::QUOTE, start));
                 quote_i += 1;
                 current_pos += 1
             }
             '=' => match vec.pop() {
-                Some(Token::EQUAL) => {
-                    vec.push(Token::OPE(EQUALITY));
+                Some((Token::EQUAL, position)) => {
+                    vec.push((Token::OPE(EQUALITY), position));
                     current_pos += 1
                 }
-                Some(Token::OPE(LesserThan)) => {
-                    vec.push(Token::OPE(LesserOrEqual));
+                Some((Token::OPE(LesserThan), position)) => {
+                    vec.push((Token::OPE(LesserOrEqual), position));
                     current_pos += 1;
                 }
-                Some(Token::OPE(GreaterThan)) => {
-                    vec.push(Token::OPE(GreaterOrEqual));
+                Some((Token::OPE(GreaterThan), position)) => {
+                    vec.push((Token::OPE(GreaterOrEqual), position));
                     current_pos += 1;
                 }
                 Some(p) => {
                     vec.push(p);
-                    vec.push(Token::EQUAL);
+                    vec.push((Token::EQUAL, start));
                     current_pos += 1
                 }
                 None => {
-                    vec.push(Token::EQUAL);
+                    vec.push((Token::EQUAL, start));
                     current_pos += 1
                 }
             },
             '&' => match vec.pop() {
-                Some(Token::PreAnd) => {
-                    vec.push(Token::OPE(And));
+                Some((Token::PreAnd, position)) => {
+                    vec.push((Token::OPE(And), position));
                     current_pos += 1;
                 }
                 Some(p) => {
                     vec.push(p);
-                    vec.push(Token::PreAnd);
+                    vec.push((Token::PreAnd, start));
                     current_pos += 1;
                 }
                 _ => {
-                    vec.push(Token::PreAnd);
+                    vec.push((Token::PreAnd, start));
                     current_pos += 1;
                 }
             },
             '|' => match vec.pop() {
-                Some(Token::PreOr) => {
-                    vec.push(Token::OPE(Or));
+                Some((Token::PreOr, position)) => {
+                    vec.push((Token::OPE(Or), position));
                     current_pos += 1;
                 }
                 Some(p) => {
                     vec.push(p);
-                    vec.push(Token::PreOr);
+                    vec.push((Token::PreOr, start));
                     current_pos += 1;
                 }
                 _ => {
-                    vec.push(Token::PreOr);
+                    vec.push((Token::PreOr, start));
                     current_pos += 1;
                 }
             },
             '^' => {
-                vec.push(Token::OPE(EXPO));
+                vec.push((Token::OPE(EXPO), start));
                 current_pos += 1
             }
             ',' => {
-                vec.push(Token::COMMA);
+                vec.push((Token::COMMA, start));
                 current_pos += 1
             }
             '!' => {
-                vec.push(Token::OPE(NOT));
+                vec.push((Token::OPE(NOT), start));
                 current_pos += 1
             }
             ']' => {
-                vec.push(Token::RBRACKET);
+                vec.push((Token::RBRACKET, start));
                 current_pos += 1
             }
             '[' => {
-                vec.push(Token::LBRACKET);
+                vec.push((Token::LBRACKET, start));
                 current_pos += 1
             }
-            ' ' => {
+            '?' => {
+                vec.push((Token::QUESTION, start));
+                current_pos += 1
+            }
+            ':' => {
+                vec.push((Token::COLON, start));
+                current_pos += 1
+            }
+            c if c.is_whitespace() => {
                 if quote_i % 2 == 1 {
-                    vec.push(Token::WHITESPACE);
+                    vec.push((Token::WHITESPACE, start));
                 }
                 current_pos += 1
             }
             ch => {
-                if ch.is_numeric() {
+                if ch.is_ascii_digit() {
                     let (a, b) = lex_int(current_character, &mut chars, current_pos, length);
                     current_pos = b;
                     let cha = chars.get(current_pos);
                     match cha {
                         Some(char) => {
-                            if *char == '.' {
-                                let (a1, b1) = lex_float(a, &mut chars, current_pos, length);
+                            if *char == '.' && chars.get(current_pos + 1) != Some(&'.') {
+                                let (a1, b1) = lex_float(&a, &mut chars, current_pos, length);
                                 current_pos = b1;
-                                vec.push(Token::FLOAT(a1))
+                                vec.push((Token::FLOAT(a1), start))
                             } else {
-                                vec.push(Token::INT(a));
+                                vec.push((int_token(&a), start));
                                 current_pos = b;
                             }
                         }
                         None => {
-                            vec.push(Token::INT(a));
+                            vec.push((int_token(&a), start));
                             current_pos = b;
                         }
                     }
                 }
-                if ch.is_alphabetic() || ch == '_' {
+                if ch.is_alphabetic() || ch == '_' || ch == '$' {
                     let (a, b) = lex_string(current_character, &mut chars, current_pos, length);
                     current_pos = b;
                     if &a == "false" {
-                        vec.push(Token::BOOL(false))
+                        vec.push((Token::BOOL(false), start))
                     } else if &a == "true" {
-                        vec.push(Token::BOOL(true))
+                        vec.push((Token::BOOL(true), start))
                     } else if &a == "or" {
-                        vec.push(Token::OPE(Or))
+                        vec.push((Token::OPE(Or), start))
                     } else if &a == "and" {
-                        vec.push(Token::OPE(And))
+                        vec.push((Token::OPE(And), start))
                     } else if &a == "geq" {
-                        vec.push(Token::OPE(GreaterOrEqual))
+                        vec.push((Token::OPE(GreaterOrEqual), start))
                     } else if &a == "leq" {
-                        vec.push(Token::OPE(LesserOrEqual))
+                        vec.push((Token::OPE(LesserOrEqual), start))
                     } else if &a == "lt" {
-                        vec.push(Token::OPE(LesserThan))
+                        vec.push((Token::OPE(LesserThan), start))
                     } else if &a == "gt" {
-                        vec.push(Token::OPE(GreaterThan))
+                        vec.push((Token::OPE(GreaterThan), start))
                     } else if &a == "eq" {
-                        vec.push(Token::OPE(EQUALITY))
+                        vec.push((Token::OPE(EQUALITY), start))
+                    } else if &a == "if" {
+                        vec.push((Token::IF, start))
+                    } else if &a == "then" {
+                        vec.push((Token::THEN, start))
+                    } else if &a == "else" {
+                        vec.push((Token::ELSE, start))
                     } else {
-                        vec.push(Token::IDENTIFIER(a))
+                        vec.push((Token::IDENTIFIER(a), start))
                     }
                 }
-                if ch == '.' {
-                    let (a, b) = lex_float(0, &mut chars, current_pos, length);
+                if ch == '.' && chars.get(current_pos + 1) == Some(&'.') {
+                    vec.push((Token::RANGE, start));
+                    current_pos += 2
+                } else if ch == '.' {
+                    let (a, b) = lex_float("0", &mut chars, current_pos, length);
                     current_pos = b;
-                    vec.push(Token::FLOAT(a))
+                    vec.push((Token::FLOAT(a), start))
+                }
+                if current_pos == start {
+                    vec.push((Token::ILLEGAL(chars[current_pos]), start));
+                    current_pos += 1
                 }
             }
         }
     }
-    let mut result = Vec::new();
-    vec.iter()
-        .filter(|x| x != &&Token::PreOr && x != &&PreAnd)
-        .for_each(|x1| result.push(x1.clone()));
-    result
+    vec.into_iter()
+        .map(|(token, position)| match token {
+            PreAnd => (Token::ILLEGAL('&'), position),
+            PreOr => (Token::ILLEGAL('|'), position),
+            t => (t, position),
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::lexing::lexer::lex;
+    use std::str::FromStr;
+
+    use num_bigint::BigInt;
+
+    use crate::lexing::lexer::{lex, lex_with_positions};
     use crate::lexing::token::Operator::*;
     use crate::lexing::token::Token::*;
 
@@ -435,6 +491,31 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_conditional_keywords() {
+        let mut expected = Vec::new();
+        expected.push(IF);
+        expected.push(BOOL(true));
+        expected.push(THEN);
+        expected.push(INT(1));
+        expected.push(ELSE);
+        expected.push(INT(2));
+        let result = lex("if true then 1 else 2".to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_ternary() {
+        let mut expected = Vec::new();
+        expected.push(BOOL(true));
+        expected.push(QUESTION);
+        expected.push(INT(1));
+        expected.push(COLON);
+        expected.push(INT(2));
+        let result = lex("true ? 1 : 2".to_string());
+        assert_eq!(result, expected)
+    }
+
     #[test]
     fn test_complex_float() {
         let mut expected = Vec::new();
@@ -442,4 +523,69 @@ mod tests {
         let result = lex("314.05".to_string());
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn test_big_int() {
+        let digits = "99999999999999999999999";
+        let expected = vec![BigInteger(BigInt::from_str(digits).unwrap())];
+        let result = lex(digits.to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_positions() {
+        let mut expected = Vec::new();
+        expected.push((LPAR, 0));
+        expected.push((INT(12), 1));
+        expected.push((OPE(LesserOrEqual), 4));
+        expected.push((IDENTIFIER("x".to_string()), 7));
+        expected.push((RPAR, 8));
+        let result = lex_with_positions("(12 <= x)".to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_illegal_char() {
+        let mut expected = Vec::new();
+        expected.push((INT(1), 0));
+        expected.push((ILLEGAL('#'), 2));
+        expected.push((INT(2), 4));
+        let result = lex_with_positions("1 # 2".to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_result_reference() {
+        let mut expected = Vec::new();
+        expected.push(IDENTIFIER("$12".to_string()));
+        expected.push(OPE(PLUS));
+        expected.push(IDENTIFIER("ans".to_string()));
+        let result = lex("$12+ans".to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_arrow() {
+        let mut expected = Vec::new();
+        expected.push((IDENTIFIER("x".to_string()), 0));
+        expected.push((ARROW, 2));
+        expected.push((IDENTIFIER("x".to_string()), 5));
+        expected.push((OPE(MINUS), 7));
+        expected.push((OPE(GreaterThan), 9));
+        expected.push((INT(1), 11));
+        let result = lex_with_positions("x -> x - > 1".to_string());
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_range() {
+        let mut expected = Vec::new();
+        expected.push((INT(1), 0));
+        expected.push((RANGE, 1));
+        expected.push((FLOAT(2.5), 3));
+        expected.push((IDENTIFIER("step".to_string()), 7));
+        expected.push((FLOAT(0.5), 12));
+        let result = lex_with_positions("1..2.5 step .5".to_string());
+        assert_eq!(result, expected)
+    }
 }
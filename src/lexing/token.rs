@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use num_bigint::BigInt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operator {
     PLUS,
@@ -22,6 +24,7 @@ pub enum Token {
     OPE(Operator),
     IDENTIFIER(String),
     INT(i64),
+    BigInteger(BigInt),
     FLOAT(f64),
     BOOL(bool),
     EQUAL,
@@ -35,6 +38,14 @@ pub enum Token {
     WHITESPACE,
     PreAnd,
     PreOr,
+    IF,
+    THEN,
+    ELSE,
+    QUESTION,
+    COLON,
+    ARROW,
+    RANGE,
+    ILLEGAL(char),
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
@@ -65,6 +76,14 @@ pub enum TokenType {
     WHITESPACE,
     EXPO,
     QUOTE,
+    IF,
+    THEN,
+    ELSE,
+    QUESTION,
+    COLON,
+    ARROW,
+    RANGE,
+    ILLEGAL,
 }
 
 pub enum Precedence {
@@ -108,6 +127,7 @@ impl Display for Token {
             Token::EQUAL => write!(f, "="),
             Token::FLOAT(i) => write!(f, "{}", i),
             Token::INT(i) => write!(f, "{}", i),
+            Token::BigInteger(i) => write!(f, "{}", i),
             Token::IDENTIFIER(s) => write!(f, "{}", s),
             Token::OPE(s) => write!(f, "{}", s),
             Token::COMMA => write!(f, ","),
@@ -119,6 +139,55 @@ impl Display for Token {
             Token::LBRACKET => write!(f, "["),
             Token::QUOTE => write!(f, "\""),
             Token::WHITESPACE => write!(f, " "),
+            Token::IF => write!(f, "if"),
+            Token::THEN => write!(f, "then"),
+            Token::ELSE => write!(f, "else"),
+            Token::QUESTION => write!(f, "?"),
+            Token::COLON => write!(f, ":"),
+            Token::ARROW => write!(f, "->"),
+            Token::RANGE => write!(f, ".."),
+            Token::ILLEGAL(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+impl Display for TokenType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenType::PLUS => write!(f, "+"),
+            TokenType::MINUS => write!(f, "-"),
+            TokenType::MULTIPLICATION => write!(f, "*"),
+            TokenType::DIVIDE => write!(f, "/"),
+            TokenType::IDENTIFIER => write!(f, "identifier"),
+            TokenType::INT => write!(f, "integer"),
+            TokenType::FLOAT => write!(f, "float"),
+            TokenType::EQUAL => write!(f, "="),
+            TokenType::EQUALITY => write!(f, "=="),
+            TokenType::GREATER => write!(f, ">"),
+            TokenType::LESSER => write!(f, "<"),
+            TokenType::GREATEREQ => write!(f, ">="),
+            TokenType::OR => write!(f, "||"),
+            TokenType::AND => write!(f, "&&"),
+            TokenType::LESSEREQ => write!(f, "<="),
+            TokenType::NOT => write!(f, "!"),
+            TokenType::BOOL => write!(f, "boolean"),
+            TokenType::RPAR => write!(f, ")"),
+            TokenType::LPAR => write!(f, "("),
+            TokenType::RBRACKET => write!(f, "]"),
+            TokenType::LBRACKET => write!(f, "["),
+            TokenType::Null => write!(f, "Null"),
+            TokenType::COMMA => write!(f, ","),
+            TokenType::WHITESPACE => write!(f, " "),
+            TokenType::EXPO => write!(f, "^"),
+            TokenType::QUOTE => write!(f, "\""),
+            TokenType::IF => write!(f, "if"),
+            TokenType::THEN => write!(f, "then"),
+            TokenType::ELSE => write!(f, "else"),
+            TokenType::QUESTION => write!(f, "?"),
+            TokenType::COLON => write!(f, ":"),
+            TokenType::ARROW => write!(f, "->"),
+            TokenType::RANGE => write!(f, ".."),
+            TokenType::ILLEGAL => write!(f, "illegal character"),
         }
     }
 }
@@ -143,6 +212,7 @@ impl Token {
             },
             Token::IDENTIFIER(_) => TokenType::IDENTIFIER,
             Token::INT(_) => TokenType::INT,
+            Token::BigInteger(_) => TokenType::INT,
             Token::FLOAT(_) => TokenType::FLOAT,
             Token::EQUAL => TokenType::EQUAL,
             Token::RPAR => TokenType::RPAR,
@@ -154,6 +224,14 @@ impl Token {
             Token::RBRACKET => TokenType::RBRACKET,
             Token::QUOTE => TokenType::QUOTE,
             Token::WHITESPACE => TokenType::WHITESPACE,
+            Token::IF => TokenType::IF,
+            Token::THEN => TokenType::THEN,
+            Token::ELSE => TokenType::ELSE,
+            Token::QUESTION => TokenType::QUESTION,
+            Token::COLON => TokenType::COLON,
+            Token::ARROW => TokenType::ARROW,
+            Token::RANGE => TokenType::RANGE,
+            Token::ILLEGAL(_) => TokenType::ILLEGAL,
             _ => TokenType::Null,
         }
     }
@@ -343,4 +421,18 @@ mod test {
         let value = Token::QUOTE.to_token_type();
         assert_eq!(value, expected);
     }
+
+    #[test]
+    fn test_token_type_question() {
+        let expected = TokenType::QUESTION;
+        let value = Token::QUESTION.to_token_type();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_token_type_if() {
+        let expected = TokenType::IF;
+        let value = Token::IF.to_token_type();
+        assert_eq!(value, expected);
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::env::{self, Args};
-use std::f64::consts::{E, PI};
+use std::fs;
+use std::io::{self, IsTerminal, Read};
 use std::process::exit;
 use std::str::SplitWhitespace;
 use std::sync::Arc;
@@ -10,18 +11,24 @@ use configuration::loader::Config;
 use linefeed::{Completer, Completion, Interface, ReadResult, Terminal};
 
 use crate::configuration::loader::{
-    load, load_config, write_config, write_default_config, Greeting, Loaded, Prompt,
+    history_path, load, load_config, write_config, write_default_config, Greeting, Loaded, Prompt,
 };
 use crate::interpreting::interpreter::interpret;
-use crate::lexing::lexer::lex;
+use crate::interpreting::stdlib::{set_max_recursion_depth, set_stack_size};
+use crate::lexing::lexer::lex_with_positions;
+use crate::lexing::token::Token;
 use crate::parsing::ast::{Ast, Parameters};
 use crate::parsing::parser::{init_calc_parser, CalcParser};
+use crate::script::{
+    format_error, init_ram, remember_result, run_script, save_session, unknown_to_error,
+};
 
 mod configuration;
 mod exact_math;
 mod interpreting;
 mod lexing;
 mod parsing;
+mod script;
 mod utils;
 
 fn show_config(config: Config) -> (String, Option<Config>) {
@@ -36,8 +43,8 @@ fn show_config(config: Config) -> (String, Option<Config>) {
     let prompt_color_message = loaded.prompt_style.paint(config.prompt.prompt_color);
     let general_message_color = loaded.general_color.paint(config.general_color);
     let general_message = loaded.general_color.paint("This is the general colour");
-    println!(" The greeting colour is set to {} which prints \n {} \n The prompt is {} in {} \n Main color is {} which looks like \n {} \n If you've modified your config and it doesn't look good, the author (Charlotte Thomas) declines any responsabilities.\n",color_message,
-    show_message,prompt,prompt_color_message,general_message_color,general_message);
+    println!(" The greeting colour is set to {} which prints \n {} \n The prompt is {} in {} \n Main color is {} which looks like \n {} \n The maximum recursion depth is {} \n The history keeps the last {} lines \n If you've modified your config and it doesn't look good, the author (Charlotte Thomas) declines any responsabilities.\n",color_message,
+    show_message,prompt,prompt_color_message,general_message_color,general_message,config.max_recursion_depth,config.max_history_size);
     ("".to_string(), None)
 }
 
@@ -80,6 +87,8 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                             general_color: (s.to_string()),
                             greeting: (config.greeting),
                             prompt: (config.prompt),
+                            max_recursion_depth: config.max_recursion_depth,
+                            max_history_size: config.max_history_size,
                         };
                         match write_config(&cfg) {
                             Ok(_) => (format!("Greeting color has been set to {}, reload for this to take effect\n",&s).to_string(),None),
@@ -104,6 +113,8 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 prompt: s.to_string(),
                                 prompt_color: config.prompt.prompt_color,
                             },
+                            max_recursion_depth: config.max_recursion_depth,
+                            max_history_size: config.max_history_size,
                         };
 
                         match write_config(&cfg) {
@@ -140,6 +151,8 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 prompt: config.prompt.prompt,
                                 prompt_color: s.to_string(),
                             },
+                            max_recursion_depth: config.max_recursion_depth,
+                            max_history_size: config.max_history_size,
                         };
 
                         match write_config(&cfg) {
@@ -167,6 +180,8 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 greeting_message: config.greeting.greeting_message,
                             },
                             prompt: config.prompt,
+                            max_recursion_depth: config.max_recursion_depth,
+                            max_history_size: config.max_history_size,
                         };
 
                         match write_config(&cfg) {
@@ -194,6 +209,8 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                                 greeting_color: config.greeting.greeting_color,
                             },
                             prompt: config.prompt,
+                            max_recursion_depth: config.max_recursion_depth,
+                            max_history_size: config.max_history_size,
                         };
 
                         match write_config(&cfg) {
@@ -213,6 +230,58 @@ fn set_config(config: Config, args: &mut SplitWhitespace) -> (String, Option<Con
                     }
                 }
             }
+            Some("max_recursion_depth") => match args.nth(0).map(|x| x.parse::<usize>()) {
+                Some(Ok(depth)) => {
+                    let cfg = Config {
+                        general_color: config.general_color,
+                        greeting: config.greeting,
+                        prompt: config.prompt,
+                        max_recursion_depth: depth,
+                        max_history_size: config.max_history_size,
+                    };
+
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Maximum recursion depth has been set to {depth}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                _ => (
+                    "You need a positive integer for this command\n".to_string(),
+                    None,
+                ),
+            },
+            Some("max_history_size") => match args.nth(0).map(|x| x.parse::<usize>()) {
+                Some(Ok(size)) => {
+                    let cfg = Config {
+                        general_color: config.general_color,
+                        greeting: config.greeting,
+                        prompt: config.prompt,
+                        max_recursion_depth: config.max_recursion_depth,
+                        max_history_size: size,
+                    };
+
+                    match write_config(&cfg) {
+                        Ok(_) => (
+                            format!("Maximum history size has been set to {size}\n"),
+                            Some(cfg),
+                        ),
+                        _ => (
+                            "An error occured while writing the config\n".to_string(),
+                            None,
+                        ),
+                    }
+                }
+                _ => (
+                    "You need a positive integer for this command\n".to_string(),
+                    None,
+                ),
+            },
             _ => (
                 "You need more argument for this command\n".to_string(),
                 None,
@@ -256,7 +325,43 @@ fn handle_config(line: &str, config: Config) -> (String, Option<Config>) {
     }
 }
 
+fn session_command<'a>(command: &str, line: &'a str) -> Option<&'a str> {
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some(c), Some(file), None) if c == command => Some(file),
+        _ => None,
+    }
+}
+
+fn execute_script(source: &str) -> ! {
+    let mut ram: HashMap<String, Parameters> = init_ram();
+    let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
+    match run_script(source, &mut ram, &mut functions, &mut io::stdout()) {
+        Ok(()) => exit(0),
+        Err(e) => {
+            eprintln!(
+                "{}",
+                Color::Red.paint(format!("line {}:\n{}", e.line, e.message))
+            );
+            exit(1);
+        }
+    }
+}
+
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 fn main() {
+    let child = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap();
+    if child.join().is_err() {
+        exit(1);
+    }
+}
+
+fn run() {
+    set_stack_size(STACK_SIZE);
     let mut args: Args = env::args();
     if args.len() > 1 {
         args.nth(0);
@@ -264,37 +369,66 @@ fn main() {
         args.for_each(|f| a.push(f));
         let arg_final = a.join("");
 
-
         if arg_final == "-h" || arg_final == "--help" {
-
             println!("-----Help Calc-----");
             println!("");
             println!("mini-calc > launch the mini-calc REPL");
             println!("mini-calc [arg] > compute non interactively");
+            println!("mini-calc run [file] > execute a calc script");
+            println!("[command] | mini-calc > execute a calc script read from stdin");
             println!("mini-calc -h || --help > open this help");
             println!("");
             println!("------Help Calc-----");
             exit(0);
+        }
+
+        if let Ok(cfg) = load() {
+            set_max_recursion_depth(cfg.max_recursion_depth);
+        }
 
+        if a.len() == 2 && a[0] == "run" {
+            match fs::read_to_string(&a[1]) {
+                Ok(source) => execute_script(&source),
+                Err(e) => {
+                    eprintln!("{}", Color::Red.paint(format!("cannot read {}: {e}", a[1])));
+                    exit(1);
+                }
+            }
         }
 
-        let lexed = lex(arg_final);
-        let mut parser = init_calc_parser(&lexed);
+        let (lexed, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions(arg_final.clone()).into_iter().unzip();
+        let mut parser = init_calc_parser(&lexed).with_positions(positions);
         let parsed = parser.parse();
-        let mut ram: HashMap<String, Parameters> = HashMap::new();
+        let mut ram: HashMap<String, Parameters> = init_ram();
         let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-        ram.insert("pi".to_string(), Parameters::Float(PI));
-        ram.insert("e".to_string(), Parameters::Float(E));
-        let result = interpret(&parsed, &mut ram, &mut functions);
-        if result != Parameters::Null {
-            println!(
+        let result = unknown_to_error(interpret(&parsed, &mut ram, &mut functions), &ram);
+        match result {
+            Parameters::Error(e) => {
+                eprintln!("{}", Color::Red.paint(format_error(&e, &arg_final)));
+                exit(1);
+            }
+            Parameters::Null => (),
+            _ => println!(
                 "{}",
                 result.pretty_print(Some(&mut ram), Some(&mut functions))
-            )
+            ),
         }
         exit(0);
     }
 
+    if !io::stdin().is_terminal() {
+        if let Ok(cfg) = load() {
+            set_max_recursion_depth(cfg.max_recursion_depth);
+        }
+        let mut source = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut source) {
+            eprintln!("{}", Color::Red.paint(format!("cannot read stdin: {e}")));
+            exit(1);
+        }
+        execute_script(&source);
+    }
+
     let mut config = match load() {
         Ok(config) => config,
         Err(_) => {
@@ -309,6 +443,7 @@ fn main() {
         }
     };
 
+    set_max_recursion_depth(config.max_recursion_depth);
     let mut loaded: Loaded = load_config(config.clone());
     let message = &loaded.greeting_message;
     println!("{}", message.to_string());
@@ -319,6 +454,11 @@ fn main() {
     let mut verbose = false;
     let version: String = "v2.12.2".to_string();
     interface.set_completer(Arc::new(CalcCompleter));
+    interface.set_history_size(config.max_history_size);
+    let history_file = history_path();
+    if let Some(path) = &history_file {
+        let _ = interface.load_history(path);
+    }
     interface
         .set_prompt(&format!(
             "\x01{prefix}\x02{text}\x01{suffix}\x02",
@@ -328,10 +468,10 @@ fn main() {
         ))
         .unwrap();
 
-    let mut ram: HashMap<String, Parameters> = HashMap::new();
+    let mut ram: HashMap<String, Parameters> = init_ram();
     let mut functions: HashMap<String, (Vec<Ast>, Ast)> = HashMap::new();
-    ram.insert("pi".to_string(), Parameters::Float(PI));
-    ram.insert("e".to_string(), Parameters::Float(E));
+    let mut results: usize = 0;
+    let mut history: Vec<(String, String, String)> = Vec::new();
     while let ReadResult::Input(line) = interface.read_line().unwrap() {
         match line.as_str().trim() {
             "info" => {
@@ -341,7 +481,7 @@ fn main() {
             "exit" => break,
             "help" => {
                 let message = loaded.general_color.paint(format!(
-                    " Calc {version} Help \n > info : show infos \n > exit : exit the program \n > help : print this help \n > verbose : toggle the verbose \n > version : prints the version \n > config : root of the config \n"
+                    " Calc {version} Help \n > info : show infos \n > exit : exit the program \n > help : print this help \n > verbose : toggle the verbose \n > history : list the previous results \n > version : prints the version \n > config : root of the config \n > save [file] : save variables and functions to a file \n > load [file] : load variables and functions from a file \n"
                 ));
                 println!("{}", message)
             }
@@ -349,6 +489,15 @@ fn main() {
                 let message = loaded.general_color.paint(format!(" Calc {version}\n"));
                 println!("{}", message)
             }
+            "history" => {
+                for (label, input, output) in &history {
+                    println!(
+                        "{}",
+                        loaded.general_color.paint(format!("{label:>4} > {input}"))
+                    );
+                    println!("{output}");
+                }
+            }
             "verbose" => {
                 verbose = !verbose;
                 let message = loaded.general_color.paint("You toggled the verbose : ");
@@ -361,6 +510,8 @@ fn main() {
                     match q {
                         Some(q) => {
                             config = q.clone();
+                            set_max_recursion_depth(config.max_recursion_depth);
+                            interface.set_history_size(config.max_history_size);
                             loaded = load_config(q);
                             text = &loaded.prompt;
                             interface
@@ -378,9 +529,39 @@ fn main() {
                             print!("{m}");
                         }
                     }
+                } else if let Some(file) = session_command("save", str) {
+                    match fs::write(file, save_session(&ram, &functions)) {
+                        Ok(_) => println!(
+                            "{}",
+                            loaded
+                                .general_color
+                                .paint(format!("The session has been saved to {file}"))
+                        ),
+                        Err(e) => {
+                            println!("{}", Color::Red.paint(format!("cannot write {file}: {e}")))
+                        }
+                    }
+                } else if let Some(file) = session_command("load", str) {
+                    let result = fs::read_to_string(file)
+                        .map_err(|e| format!("cannot read {file}: {e}"))
+                        .and_then(|source| {
+                            run_script(&source, &mut ram, &mut functions, &mut io::sink())
+                                .map_err(|e| format!("line {}:\n{}", e.line, e.message))
+                        });
+                    match result {
+                        Ok(()) => println!(
+                            "{}",
+                            loaded
+                                .general_color
+                                .paint(format!("The session has been loaded from {file}"))
+                        ),
+                        Err(e) => println!("{}", Color::Red.paint(e)),
+                    }
                 } else {
-                    let a = lex(str.to_string());
-                    let parser: &mut CalcParser = &mut parsing::parser::init_calc_parser(&a);
+                    let (a, positions): (Vec<Token>, Vec<usize>) =
+                        lex_with_positions(str.to_string()).into_iter().unzip();
+                    let parser: &mut CalcParser =
+                        &mut init_calc_parser(&a).with_positions(positions);
                     let p = parser.parse();
                     if verbose {
                         println!("Lexing of line: {str}");
@@ -390,29 +571,50 @@ fn main() {
                         println!()
                     }
 
-                    let result = interpret(&p, &mut ram, &mut functions);
-                    if result != Parameters::Null {
-                        println!(
-                            "{}",
-                            result.pretty_print(Some(&mut ram), Some(&mut functions))
-                        )
+                    let result = unknown_to_error(interpret(&p, &mut ram, &mut functions), &ram);
+                    match result {
+                        Parameters::Error(e) => {
+                            println!("{}", Color::Red.paint(format_error(&e, str)))
+                        }
+                        Parameters::Null => (),
+                        _ => {
+                            let output = result.pretty_print(Some(&mut ram), Some(&mut functions));
+                            println!("{}", output);
+                            let index = results + 1;
+                            let label = if remember_result(&p, &result, &mut ram, index) {
+                                results = index;
+                                format!("${index}")
+                            } else {
+                                "".to_string()
+                            };
+                            history.push((label, str.to_string(), output));
+                        }
                     }
                 }
             }
         }
-        interface.add_history_unique(line);
+        if !line.trim().is_empty() {
+            interface.add_history_unique(line);
+            if let Some(path) = &history_file {
+                let _ = interface.save_history(path);
+            }
+        }
     }
     exit(0);
 }
 
 struct CalcCompleter;
 
-static CMD: &[&str] = &["config", "exit", "verbose", "version", "help", "info"];
+static CMD: &[&str] = &[
+    "config", "exit", "verbose", "version", "help", "info", "save", "load", "history",
+];
 static CONFIG_CMD: &[&str] = &["reload", "reset", "set", "show"];
 static SET_CMD: &[&str] = &[
     "general_color",
     "greeting_color",
     "greeting_message",
+    "max_history_size",
+    "max_recursion_depth",
     "prompt",
     "prompt_color",
 ];
@@ -468,7 +670,10 @@ impl<Term: Terminal> Completer<Term> for CalcCompleter {
                         Some(co)
                     }
                     Some(c) => {
-                        if SET_CMD.contains(&c) {
+                        if SET_CMD.contains(&c)
+                            && c != "max_recursion_depth"
+                            && c != "max_history_size"
+                        {
                             let mut co = Vec::new();
                             for cmd in CMD_COLOR {
                                 if cmd.starts_with(word) {
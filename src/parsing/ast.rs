@@ -1,10 +1,17 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_traits::ToPrimitive;
+
+use crate::exact_math::algebra::Expression;
 use crate::exact_math::rationals::Rationals;
+use crate::interpreting::error::CalcError;
 use crate::lexing::token::{Operator, Token};
 use crate::parsing::ast::Ast::{Nil, Node};
 use crate::parsing::ast::Parameters::*;
+use crate::utils::complex_utils::format_complex;
 use crate::utils::matrix_utils::transpose;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +22,7 @@ pub enum Parameters {
     Str(String),
     Identifier(String),
     Rational(Rationals),
+    Complex(Complex64),
     PlusOperation,
     MinusOperation,
     MultiplicationOperation,
@@ -30,8 +38,24 @@ pub enum Parameters {
     Assign,
     Null,
     ExpoOperation,
+    SliceOperation,
     Vector(Box<Vec<Ast>>),
     InterpreterVector(Box<Vec<Parameters>>),
+    Lambda(Vec<String>, Box<Ast>),
+    Symbolic(Box<Expression>),
+    Error(CalcError),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Position {
+    pub column: Option<usize>,
+    pub span: Option<(usize, Option<usize>)>,
+}
+
+impl PartialEq for Position {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,10 +65,23 @@ pub enum Ast {
         value: Parameters,
         left: Box<Ast>,
         right: Box<Ast>,
+        position: Position,
     },
     Call {
         name: String,
         lst: Vec<Ast>,
+        position: Position,
+    },
+    If {
+        condition: Box<Ast>,
+        then: Box<Ast>,
+        otherwise: Box<Ast>,
+        position: Position,
+    },
+    Index {
+        target: Box<Ast>,
+        indices: Vec<Ast>,
+        position: Position,
     },
 }
 
@@ -61,6 +98,7 @@ impl Display for Parameters {
             Assign => write!(f, "="),
             Null => write!(f, ""),
             ExpoOperation => write!(f, "^"),
+            SliceOperation => write!(f, ":"),
             GreaterOperation => write!(f, ">"),
             LesserOperation => write!(f, "<"),
             GreaterOrEqualOperation => write!(f, ">="),
@@ -70,10 +108,25 @@ impl Display for Parameters {
             Bool(b) => write!(f, "{b}"),
             AndOperation => write!(f, "&&"),
             OrOperation => write!(f, "||"),
-            Vector(a) => write!(f, "{:?}", a),
-            InterpreterVector(a) => write!(f, "{:?}", a),
+            Vector(a) => {
+                let vs: Vec<String> = a.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", vs.join(","))
+            }
+            InterpreterVector(a) => {
+                let vs: Vec<String> = a.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", vs.join(","))
+            }
             Str(s) => write!(f, "{s}"),
             Rational(s) => write!(f, "{s}"),
+            Complex(c) => write!(f, "{}", format_complex(c)),
+            Lambda(params, body) if params.len() == 1 => {
+                write!(f, "{} -> {}", params[0], body.to_expression())
+            }
+            Lambda(params, body) => {
+                write!(f, "({}) -> {}", params.join(","), body.to_expression())
+            }
+            Symbolic(e) => write!(f, "{e}"),
+            Error(e) => write!(f, "{e}"),
         }
     }
 }
@@ -86,14 +139,42 @@ impl Display for Ast {
                 value: v,
                 left: l,
                 right: r,
+                ..
             } => {
                 write!(f, "({} {} {})", l, v, r)
             }
-            Ast::Call { name: v, lst: s } => {
+            Ast::Call {
+                name: v, lst: s, ..
+            } => {
                 let mut vs = Vec::new();
                 s.iter().for_each(|x1| vs.push(x1.to_string()));
                 write!(f, "{}({})", v, vs.join(",").to_string())
             }
+            Ast::If {
+                condition: c,
+                then: t,
+                otherwise: o,
+                ..
+            } => write!(f, "if {} then {} else {}", c, t, o),
+            Ast::Index {
+                target: t,
+                indices: i,
+                ..
+            } => {
+                let vs: Vec<String> = i
+                    .iter()
+                    .map(|x| match x {
+                        Node {
+                            value: SliceOperation,
+                            left: l,
+                            right: r,
+                            ..
+                        } => format!("{l}:{r}"),
+                        x => x.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}[{}]", t, vs.join(","))
+            }
         }
     }
 }
@@ -148,8 +229,20 @@ impl Parameters {
                     Parameters::InterpreterVector(_) => matrix = true,
                     _ => (),
                 }
+                let width = match lst.first().unwrap() {
+                    Parameters::InterpreterVector(row) => row.len(),
+                    _ => 0,
+                };
+                let rows = lst.iter().all(|x| match x {
+                    Parameters::InterpreterVector(row) => {
+                        row.len() == width && row.iter().all(|y| !matches!(y, InterpreterVector(_)))
+                    }
+                    _ => false,
+                });
                 if !matrix {
                     format!("|{}|", vec.join(" "))
+                } else if !rows || width == 0 {
+                    vec.join("\n")
                 } else {
                     let mut vss = Vec::new();
                     let mut max_size = 0;
@@ -225,6 +318,7 @@ impl Parameters {
 pub fn token_to_parameter(token: Token) -> Parameters {
     match token {
         Token::INT(i) => Int(i),
+        Token::BigInteger(i) => Parameters::from_big_int(i),
         Token::FLOAT(f) => Float(f),
         Token::IDENTIFIER(s) => Identifier(s),
         Token::OPE(Operator::PLUS) => PlusOperation,
@@ -253,16 +347,194 @@ impl Ast {
             value: p,
             left: Box::from(Nil),
             right: Box::from(Nil),
+            position: Position::default(),
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        match self {
+            Nil => Position::default(),
+            Node { position, .. }
+            | Ast::Call { position, .. }
+            | Ast::If { position, .. }
+            | Ast::Index { position, .. } => *position,
+        }
+    }
+
+    pub fn at(mut self, at: Position) -> Self {
+        match &mut self {
+            Nil => (),
+            Node { position, .. }
+            | Ast::Call { position, .. }
+            | Ast::If { position, .. }
+            | Ast::Index { position, .. } => *position = at,
+        }
+        self
+    }
+
+    pub fn spanning(self, start: usize, end: Option<usize>) -> Self {
+        let position = self.position();
+        self.at(Position {
+            span: Some((start, end)),
+            ..position
+        })
+    }
+
+    pub fn to_expression(&self) -> String {
+        self.expression().0
+    }
+
+    fn expression(&self) -> (String, u8) {
+        fn wrap((s, p): (String, u8), min: u8) -> String {
+            if p < min {
+                format!("({s})")
+            } else {
+                s
+            }
+        }
+        match self {
+            Nil => ("".to_string(), 9),
+            Node {
+                value: v,
+                left: l,
+                right: r,
+                ..
+            } => match (v, l.as_ref(), r.as_ref()) {
+                (Int(i), Nil, Nil) if *i < 0 => (i.to_string(), 5),
+                (Float(f), Nil, Nil) if *f < 0.0 => (f.to_string(), 5),
+                (Float(f), Nil, Nil) => (f.to_string(), 9),
+                (Rational(r), Nil, Nil) if r.to_int().is_none() => (r.to_string(), 4),
+                (Vector(a), Nil, Nil) => {
+                    let vs: Vec<String> = a.iter().map(|x| x.to_expression()).collect();
+                    (format!("[{}]", vs.join(", ")), 9)
+                }
+                (Lambda(..), Nil, Nil) => (v.to_string(), 0),
+                (v, Nil, Nil) => (v.to_string(), 9),
+                (MinusOperation | Not, l, Nil) => (format!("{v}{}", wrap(l.expression(), 5)), 5),
+                (PlusOperation, l, Nil) => l.expression(),
+                (ExpoOperation, l, r) => (
+                    format!("{}^{}", wrap(l.expression(), 7), wrap(r.expression(), 6)),
+                    6,
+                ),
+                (MultiplicationOperation, l, r) => {
+                    let (s, p) = r.expression();
+                    let right = if p == 5 {
+                        format!("({s})")
+                    } else {
+                        wrap((s, p), 4)
+                    };
+                    (format!("{}*{right}", wrap(l.expression(), 4)), 4)
+                }
+                (DivideOperation, l, r) => (
+                    format!("{}/{}", wrap(l.expression(), 4), wrap(r.expression(), 6)),
+                    4,
+                ),
+                (PlusOperation, l, r) => (
+                    format!("{} + {}", wrap(l.expression(), 3), wrap(r.expression(), 3)),
+                    3,
+                ),
+                (MinusOperation, l, r) => (
+                    format!("{} - {}", wrap(l.expression(), 3), wrap(r.expression(), 4)),
+                    3,
+                ),
+                (v, l, r) => (
+                    format!(
+                        "{} {v} {}",
+                        wrap(l.expression(), 3),
+                        wrap(r.expression(), 3)
+                    ),
+                    2,
+                ),
+            },
+            Ast::Call {
+                name: n, lst: l, ..
+            } => {
+                let vs: Vec<String> = l.iter().map(|x| x.to_expression()).collect();
+                (format!("{n}({})", vs.join(", ")), 9)
+            }
+            Ast::If {
+                condition: c,
+                then: t,
+                otherwise: o,
+                ..
+            } => (
+                format!(
+                    "if {} then {} else {}",
+                    c.to_expression(),
+                    t.to_expression(),
+                    o.to_expression()
+                ),
+                1,
+            ),
+            Ast::Index {
+                target: t,
+                indices: i,
+                ..
+            } => {
+                let vs: Vec<String> = i
+                    .iter()
+                    .map(|x| match x {
+                        Node {
+                            value: SliceOperation,
+                            left: l,
+                            right: r,
+                            ..
+                        } => format!("{}:{}", l.to_expression(), r.to_expression()),
+                        x => x.to_expression(),
+                    })
+                    .collect();
+                (format!("{}[{}]", wrap(t.expression(), 9), vs.join(", ")), 9)
+            }
         }
     }
 }
 
 impl Parameters {
+    pub fn from_big_int(i: BigInt) -> Parameters {
+        match i.to_i64() {
+            Some(i) => Int(i),
+            None => Rational(Rationals::from_big(BigInt::from(1), i)),
+        }
+    }
+
+    pub fn from_rational(r: Rationals) -> Parameters {
+        match r.to_int() {
+            Some(i) => Int(i),
+            None => Rational(r.reduce()),
+        }
+    }
+
+    pub fn to_rational(&self) -> Option<Rationals> {
+        match self {
+            Int(i) => Some(Rationals::new(1, *i)),
+            Rational(r) => Some(r.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Int(i) => Some(*i as f64),
+            Float(f) => Some(*f),
+            Rational(r) => Some(r.clone().approx()),
+            _ => None,
+        }
+    }
+
+    pub fn first_error(&self) -> Option<CalcError> {
+        match self {
+            Error(e) => Some(e.clone()),
+            InterpreterVector(vec) => vec.iter().find_map(|x| x.first_error()),
+            _ => None,
+        }
+    }
+
     pub fn abs(self, ram: Option<&HashMap<String, Parameters>>) -> Parameters {
         match self {
             Parameters::Int(i) => Parameters::Int(i.abs()),
             Parameters::Float(f) => Parameters::Float(f.abs()),
             Parameters::Rational(r) => Parameters::Rational(r.abs()),
+            Parameters::Complex(c) => Parameters::Float(c.norm()),
             Parameters::Identifier(s) => match ram {
                 None => Parameters::Null,
                 Some(t) => {
@@ -278,9 +550,128 @@ impl Parameters {
     }
 }
 
+fn float_to_source(f: f64) -> String {
+    if f.fract() == 0.0 && f.abs() < 2f64.powi(53) {
+        return format!("float({})", f as i64);
+    }
+    let bits = f.to_bits();
+    let sign = if f.is_sign_negative() { "-" } else { "" };
+    let exponent = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mantissa, exponent) = match exponent {
+        0 => (fraction, -1074),
+        e => (fraction | (1 << 52), e - 1075),
+    };
+    let shift = mantissa.trailing_zeros() as i64;
+    let (mantissa, exponent) = (mantissa >> shift, exponent + shift);
+    match -exponent {
+        e if e < 0 => format!("float({sign}{mantissa}*2^{})", -e),
+        e if e <= 1022 => format!("float({sign}{mantissa}/2^{e})"),
+        e => format!("(float({sign}{mantissa}/2^1022)/2^{})", e - 1022),
+    }
+}
+
+pub fn value_to_source(value: &Parameters) -> Option<String> {
+    match value {
+        Parameters::Int(i) => Some(i.to_string()),
+        Parameters::Float(f) if f.is_finite() => Some(float_to_source(*f)),
+        Parameters::Rational(r) => Some(format!("({r})")),
+        Parameters::Complex(c) if c.is_finite() => Some(format!(
+            "({} + {}*i)",
+            float_to_source(c.re),
+            float_to_source(c.im)
+        )),
+        Parameters::Bool(b) => Some(b.to_string()),
+        Parameters::Str(s) => Some(format!("\"{s}\"")),
+        Parameters::Identifier(s) if !s.starts_with("@") => Some(s.to_string()),
+        Parameters::InterpreterVector(v) => v
+            .iter()
+            .map(value_to_source)
+            .collect::<Option<Vec<String>>>()
+            .map(|v| format!("[{}]", v.join(","))),
+        Parameters::Vector(v) => v
+            .iter()
+            .map(ast_to_source)
+            .collect::<Option<Vec<String>>>()
+            .map(|v| format!("[{}]", v.join(","))),
+        Parameters::Symbolic(e) => ast_to_source(&e.to_ast()),
+        Parameters::Lambda(params, body) => Some(format!(
+            "(({}) -> {})",
+            params.join(","),
+            ast_to_source(body)?
+        )),
+        _ => None,
+    }
+}
+
+pub fn ast_to_source(ast: &Ast) -> Option<String> {
+    match ast {
+        Ast::Nil => Some("".to_string()),
+        Ast::Node {
+            value: v,
+            left: l,
+            right: r,
+            ..
+        } => match (v, l.as_ref(), r.as_ref()) {
+            (Parameters::Float(f), Ast::Nil, Ast::Nil) => Some(f.to_string()),
+            (_, Ast::Nil, Ast::Nil) => value_to_source(v),
+            (_, l, Ast::Nil) => Some(format!("({v}{})", ast_to_source(l)?)),
+            (_, l, r) => Some(format!("({} {v} {})", ast_to_source(l)?, ast_to_source(r)?)),
+        },
+        Ast::Call {
+            name: n, lst: l, ..
+        } => l
+            .iter()
+            .map(expression_source)
+            .collect::<Option<Vec<String>>>()
+            .map(|l| format!("{n}({})", l.join(","))),
+        Ast::Index {
+            target: t,
+            indices: i,
+            ..
+        } => {
+            let mut vs = Vec::new();
+            for x in i {
+                vs.push(match x {
+                    Ast::Node {
+                        value: Parameters::SliceOperation,
+                        left: l,
+                        right: r,
+                        ..
+                    } => format!("{}:{}", ast_to_source(l)?, ast_to_source(r)?),
+                    x => ast_to_source(x)?,
+                });
+            }
+            Some(format!("{}[{}]", ast_to_source(t)?, vs.join(",")))
+        }
+        Ast::If {
+            condition: c,
+            then: t,
+            otherwise: o,
+            ..
+        } => Some(format!(
+            "(if {} then {} else {})",
+            ast_to_source(c)?,
+            ast_to_source(t)?,
+            ast_to_source(o)?
+        )),
+    }
+}
+
+pub fn expression_source(ast: &Ast) -> Option<String> {
+    let source = ast_to_source(ast)?;
+    Some(match ast {
+        Ast::Node { left: l, .. } if !matches!(l.as_ref(), Ast::Nil) => {
+            source[1..source.len() - 1].to_string()
+        }
+        Ast::If { .. } => source[1..source.len() - 1].to_string(),
+        _ => source,
+    })
+}
+
 #[cfg(test)]
 mod test {
-    use crate::parsing::ast::{Ast, Parameters};
+    use crate::parsing::ast::{Ast, Parameters, Position};
 
     #[test]
     pub fn test_new() {
@@ -288,6 +679,7 @@ mod test {
             value: Parameters::Int(2),
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
+            position: Position::default(),
         };
         let result = Ast::new(Parameters::Int(2));
         assert_eq!(result, expected)
@@ -15,6 +15,14 @@ pub struct CallParselet {}
 
 pub struct NullParset {}
 
+pub struct TernaryParselet {}
+
+pub struct LambdaParselet {}
+
+pub struct IndexParselet {}
+
+pub struct RangeParselet {}
+
 pub struct OperatorInfixParselet {
     pub is_right: bool,
     pub precedence: i64,
@@ -22,6 +30,7 @@ pub struct OperatorInfixParselet {
 
 impl InfixParselet for OperatorInfixParselet {
     fn parse(&self, parser: &mut CalcParser, left: &Ast, token: Token) -> Ast {
+        let position = parser.previous_position();
         let right = parser.parse_expression(if self.is_right {
             self.get_precedence() - 1
         } else {
@@ -32,6 +41,7 @@ impl InfixParselet for OperatorInfixParselet {
             value: param,
             left: Box::new(left.clone()),
             right: Box::new(right),
+            position,
         }
     }
 
@@ -42,11 +52,13 @@ impl InfixParselet for OperatorInfixParselet {
 
 impl InfixParselet for AssignParselet {
     fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
+        let position = parser.previous_position();
         let right = parser.parse_expression_empty();
         Ast::Node {
             value: Parameters::Assign,
             left: Box::new(left.clone()),
             right: Box::new(right),
+            position,
         }
     }
 
@@ -57,21 +69,17 @@ impl InfixParselet for AssignParselet {
 
 impl InfixParselet for CallParselet {
     fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
-        let name = match left {
-            Ast::Nil => "",
+        let (name, mut lst) = match left {
             Ast::Node {
-                value: v,
-                left: _left,
-                right: _right,
-            } => match v {
-                Parameters::Identifier(s) => s.as_str(),
-                _ => "",
-            },
-            _ => "",
+                value: Parameters::Identifier(s),
+                ..
+            } => (s.to_string(), Vec::new()),
+            _ => ("apply".to_string(), vec![left.clone()]),
         };
 
-        let mut lst: Vec<Ast> = Vec::new();
-        if !parser.match_token(TokenType::RPAR) {
+        if parser.match_token(TokenType::RPAR) {
+            parser.consume();
+        } else {
             lst.push(parser.parse_expression_empty());
             while parser.match_token(TokenType::COMMA) {
                 parser.consume();
@@ -81,8 +89,111 @@ impl InfixParselet for CallParselet {
             parser.consume_expected(TokenType::RPAR);
         }
         Call {
-            name: name.to_string(),
+            name,
             lst,
+            position: left.position(),
+        }
+    }
+
+    fn get_precedence(&self) -> i64 {
+        Precedence::CALL as i64
+    }
+}
+
+impl InfixParselet for TernaryParselet {
+    fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
+        let position = parser.previous_position();
+        let then = parser.parse_expression_empty();
+        parser.consume_expected(TokenType::COLON);
+        let otherwise = parser.parse_expression(self.get_precedence() - 1);
+        Ast::If {
+            condition: Box::new(left.clone()),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+            position,
+        }
+    }
+
+    fn get_precedence(&self) -> i64 {
+        Precedence::CONDITIONAL as i64
+    }
+}
+
+pub fn lambda_parameter(ast: &Ast) -> Option<String> {
+    match ast {
+        Ast::Node {
+            value: Parameters::Identifier(s),
+            left: l,
+            right: r,
+            ..
+        } if **l == Ast::Nil && **r == Ast::Nil => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+pub fn parse_lambda(parser: &mut CalcParser, parameters: Vec<&Ast>) -> Ast {
+    let position = parser.previous_position();
+    let mut names = Vec::new();
+    for p in parameters {
+        match lambda_parameter(p) {
+            Some(s) => names.push(s),
+            None => {
+                parser.report_previous("the parameters of a lambda must be names".to_string());
+                return Ast::Nil;
+            }
+        }
+    }
+    let body = parser.parse_expression_empty();
+    Ast::new(Parameters::Lambda(names, Box::from(body))).at(position)
+}
+
+impl InfixParselet for LambdaParselet {
+    fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
+        parse_lambda(parser, vec![left])
+    }
+
+    fn get_precedence(&self) -> i64 {
+        Precedence::ASSIGNMENT as i64
+    }
+}
+
+fn parse_index(parser: &mut CalcParser) -> Ast {
+    let from = if parser.match_token(TokenType::COLON) {
+        Ast::Nil
+    } else {
+        parser.parse_expression_empty()
+    };
+    if !parser.match_token(TokenType::COLON) {
+        return from;
+    }
+    parser.consume();
+    let position = parser.previous_position();
+    let to = if parser.match_token(TokenType::RBRACKET) || parser.match_token(TokenType::COMMA) {
+        Ast::Nil
+    } else {
+        parser.parse_expression_empty()
+    };
+    Ast::Node {
+        value: Parameters::SliceOperation,
+        left: Box::new(from),
+        right: Box::new(to),
+        position,
+    }
+}
+
+impl InfixParselet for IndexParselet {
+    fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
+        let position = parser.previous_position();
+        let mut indices = vec![parse_index(parser)];
+        while parser.match_token(TokenType::COMMA) {
+            parser.consume();
+            indices.push(parse_index(parser));
+        }
+        parser.consume_expected(TokenType::RBRACKET);
+        Ast::Index {
+            target: Box::new(left.clone()),
+            indices,
+            position,
         }
     }
 
@@ -91,6 +202,30 @@ impl InfixParselet for CallParselet {
     }
 }
 
+impl InfixParselet for RangeParselet {
+    fn parse(&self, parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
+        let position = parser.previous_position();
+        let mut lst = vec![left.clone(), parser.parse_expression(self.get_precedence())];
+        if parser.match_token(TokenType::IDENTIFIER) {
+            match parser.consume() {
+                Token::IDENTIFIER(s) if s == "step" => {
+                    lst.push(parser.parse_expression(self.get_precedence()))
+                }
+                t => parser.unexpected(t),
+            }
+        }
+        Call {
+            name: "range".to_string(),
+            lst,
+            position,
+        }
+    }
+
+    fn get_precedence(&self) -> i64 {
+        Precedence::CONDITIONAL as i64
+    }
+}
+
 impl InfixParselet for NullParset {
     fn parse(&self, _parser: &mut CalcParser, left: &Ast, _token: Token) -> Ast {
         left.clone()
@@ -1,5 +1,6 @@
-use crate::lexing::token::{Token, TokenType};
+use crate::lexing::token::{Precedence, Token, TokenType};
 use crate::parsing::ast::{token_to_parameter, Ast};
+use crate::parsing::parselets::infix_parselet::parse_lambda;
 use crate::parsing::parser::CalcParser;
 
 pub trait PrefixParselet {
@@ -24,29 +25,42 @@ pub struct VecParselet {}
 #[derive(Clone)]
 pub struct QuoteParselet {}
 
+#[derive(Clone)]
+pub struct IfParselet {}
+
 impl PrefixParselet for ValueParselet {
-    fn parse(&self, _parser: &mut CalcParser, token: Token) -> Ast {
+    fn parse(&self, parser: &mut CalcParser, token: Token) -> Ast {
+        let position = parser.previous_position();
         Ast::Node {
             value: token_to_parameter(token),
             left: Box::from(Ast::Nil),
             right: Box::from(Ast::Nil),
+            position,
         }
     }
 }
 
 impl PrefixParselet for OperatorPrefixParselet {
     fn parse(&self, parser: &mut CalcParser, token: Token) -> Ast {
-        let operand = parser.parse_expression_empty();
+        let position = parser.previous_position();
+        let operand = parser.parse_expression(Precedence::CONDITIONAL as i64);
+        let operand = if parser.match_token(TokenType::RANGE) {
+            operand
+        } else {
+            parser.parse_infix(operand, 0)
+        };
         Ast::Node {
             value: token_to_parameter(token),
             left: Box::from(operand),
             right: Box::from(Ast::Nil),
+            position,
         }
     }
 }
 
 impl PrefixParselet for NullParselet {
-    fn parse(&self, _parser: &mut CalcParser, _token: Token) -> Ast {
+    fn parse(&self, parser: &mut CalcParser, token: Token) -> Ast {
+        parser.unexpected(token);
         Ast::Nil
     }
 }
@@ -54,6 +68,16 @@ impl PrefixParselet for NullParselet {
 impl PrefixParselet for GroupParselet {
     fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
         let expression = parser.parse_expression_empty();
+        if parser.match_token(TokenType::COMMA) {
+            let mut parameters = vec![expression];
+            while parser.match_token(TokenType::COMMA) {
+                parser.consume();
+                parameters.push(parser.parse_expression_empty());
+            }
+            parser.consume_expected(TokenType::RPAR);
+            parser.consume_expected(TokenType::ARROW);
+            return parse_lambda(parser, parameters.iter().collect());
+        }
         parser.consume_expected(TokenType::RPAR);
         expression
     }
@@ -61,6 +85,7 @@ impl PrefixParselet for GroupParselet {
 
 impl PrefixParselet for VecParselet {
     fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
+        let position = parser.previous_position();
         let mut vec: Vec<Ast> = Vec::new();
 
         if !parser.match_token(TokenType::RBRACKET) {
@@ -69,24 +94,30 @@ impl PrefixParselet for VecParselet {
                 parser.consume();
                 vec.push(parser.parse_expression_empty());
             }
-            parser.consume_expected(TokenType::RBRACKET);
         }
+        parser.consume_expected(TokenType::RBRACKET);
 
         Ast::Node {
             value: crate::parsing::ast::Parameters::Vector(Box::from(vec)),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
+            position,
         }
     }
 }
 
 impl PrefixParselet for QuoteParselet {
     fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
+        let position = parser.previous_position();
         let mut str: String = String::new();
 
         if !parser.match_token(TokenType::QUOTE) {
             while !parser.match_token(TokenType::QUOTE) {
                 match parser.consume() {
+                    Token::Null => {
+                        parser.report_previous("unterminated string".to_string());
+                        break;
+                    }
                     Token::IDENTIFIER(s) => str = str + &s.to_string(),
 
                     t => str = str + &t.to_string(),
@@ -99,6 +130,25 @@ impl PrefixParselet for QuoteParselet {
             value: crate::parsing::ast::Parameters::Str(str.trim().to_string()),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
+            position,
+        }
+    }
+}
+
+impl PrefixParselet for IfParselet {
+    fn parse(&self, parser: &mut CalcParser, _token: Token) -> Ast {
+        let position = parser.previous_position();
+        let condition = parser.parse_expression_empty();
+        parser.consume_expected(TokenType::THEN);
+        let then = parser.parse_expression_empty();
+        parser.consume_expected(TokenType::ELSE);
+        let otherwise = parser.parse_expression_empty();
+
+        Ast::If {
+            condition: Box::from(condition),
+            then: Box::from(then),
+            otherwise: Box::from(otherwise),
+            position,
         }
     }
 }
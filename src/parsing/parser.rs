@@ -1,48 +1,145 @@
 use core::slice::Iter;
 
+use crate::interpreting::error::{CalcError, ErrorKind};
 use crate::lexing::token::Token::*;
 use crate::lexing::token::{Precedence, Token, TokenType};
-use crate::parsing::ast::Ast;
+use crate::parsing::ast::{Ast, Parameters, Position};
 use crate::parsing::parselets::infix_parselet::{
-    AssignParselet, CallParselet, InfixParselet, NullParset, OperatorInfixParselet,
+    AssignParselet, CallParselet, IndexParselet, InfixParselet, LambdaParselet, NullParset,
+    OperatorInfixParselet, RangeParselet, TernaryParselet,
 };
 use crate::parsing::parselets::prefix_parselet::{
     GroupParselet, NullParselet, OperatorPrefixParselet, PrefixParselet, ValueParselet,
 };
 
-use super::parselets::prefix_parselet::{QuoteParselet, VecParselet};
+use super::parselets::prefix_parselet::{IfParselet, QuoteParselet, VecParselet};
 
 #[derive(Clone)]
 pub struct CalcParser<'a> {
     tokens: Iter<'a, Token>,
     read: Vec<Token>,
+    positions: Vec<usize>,
+    end: Option<usize>,
+    consumed: usize,
+    error: Option<CalcError>,
 }
 
 pub fn init_calc_parser(input: &Vec<Token>) -> CalcParser {
     CalcParser {
         tokens: input.iter(),
         read: Vec::new(),
+        positions: Vec::new(),
+        end: None,
+        consumed: 0,
+        error: None,
     }
 }
 
 impl CalcParser<'_> {
+    pub fn with_positions(mut self, positions: Vec<usize>) -> Self {
+        self.end = match (positions.last(), self.tokens.clone().last()) {
+            (Some(p), Some(t)) => Some(p + t.to_string().chars().count()),
+            _ => None,
+        };
+        self.positions = positions;
+        self
+    }
+
     pub fn parse(&mut self) -> Ast {
-        self.parse_expression_empty()
+        let ast = self.parse_expression_empty();
+        let token = self.look_ahead(0);
+        if token != Null {
+            self.report(format!("unexpected '{token}'"), self.consumed);
+        }
+        match self.error.take() {
+            None => ast,
+            Some(e) => Ast::Node {
+                value: Parameters::Error(e),
+                left: Box::from(Ast::Nil),
+                right: Box::from(Ast::Nil),
+                position: Position::default(),
+            },
+        }
     }
+
+    fn report(&mut self, message: String, index: usize) {
+        if self.error.is_some() {
+            return;
+        }
+        let error = CalcError::new(ErrorKind::Syntax, message);
+        let position = match self.positions.get(index) {
+            Some(p) => Some(*p),
+            None => self.end,
+        };
+        self.error = Some(match position {
+            Some(p) => error.with_position(p),
+            None => error,
+        });
+    }
+
+    pub fn previous_position(&self) -> Position {
+        Position {
+            column: self
+                .consumed
+                .checked_sub(1)
+                .and_then(|i| self.positions.get(i))
+                .copied(),
+            span: None,
+        }
+    }
+
+    fn next_position(&self) -> Option<usize> {
+        self.positions.get(self.consumed).copied()
+    }
+
+    fn spanned(&self, ast: Ast, start: Option<usize>) -> Ast {
+        match start {
+            Some(s) => ast.spanning(s, self.next_position()),
+            None => ast,
+        }
+    }
+
+    pub fn report_previous(&mut self, message: String) {
+        self.report(message, self.consumed.saturating_sub(1));
+    }
+
+    pub fn unexpected(&mut self, token: Token) {
+        match token {
+            Null if self.consumed == 0 => (),
+            Null => self.report("unexpected end of input".to_string(), self.consumed),
+            t => self.report(format!("unexpected '{t}'"), self.consumed - 1),
+        }
+    }
+
     pub fn parse_expression(&mut self, precedence: i64) -> Ast {
-        let mut token = self.consume();
+        let start = self.next_position();
+        let token = self.consume();
         let prefix = self
             .clone()
             .get_prefix_parselet(token.clone().to_token_type());
 
-        let mut left = prefix.unwrap().parse(self, token.clone());
+        let left = prefix.unwrap().parse(self, token.clone());
+        let left = match left.position().span {
+            None => self.spanned(left, start),
+            Some(_) => left,
+        };
+        self.parse_infix_from(left, precedence, start)
+    }
+
+    pub fn parse_infix(&mut self, left: Ast, precedence: i64) -> Ast {
+        let start = left.position().span.map(|(s, _)| s);
+        self.parse_infix_from(left, precedence, start)
+    }
+
+    fn parse_infix_from(&mut self, mut left: Ast, precedence: i64, start: Option<usize>) -> Ast {
         while precedence < self.get_precedence() {
-            token = self.consume();
+            let token = self.consume();
             let parser = self
                 .clone()
                 .get_infix_parselet(token.clone().to_token_type())
                 .unwrap();
             left = parser.parse(self, &left, token);
+            left = self.spanned(left, start);
         }
         left
     }
@@ -67,6 +164,7 @@ impl CalcParser<'_> {
         if self.read.len() == 0 {
             return Null;
         }
+        self.consumed += 1;
         self.read.remove(0)
     }
 
@@ -81,14 +179,22 @@ impl CalcParser<'_> {
     pub fn consume_expected(&mut self, expected: TokenType) -> Token {
         self.look_ahead(0);
         if self.read.len() == 0 {
+            self.report(
+                format!("expected '{}' but found end of input", expected),
+                self.consumed,
+            );
             return Null;
         }
+        self.consumed += 1;
         match self.read.remove(0) {
             t => {
                 if t.to_token_type() == expected {
                     t
                 } else {
-                    println!("error!");
+                    self.report(
+                        format!("expected '{}' but found '{}'", expected, t),
+                        self.consumed - 1,
+                    );
                     Null
                 }
             }
@@ -161,6 +267,10 @@ impl CalcParser<'_> {
                 is_right: false,
                 precedence: (Precedence::CONDITIONAL as i64),
             })),
+            TokenType::QUESTION => Some(Box::from(TernaryParselet {})),
+            TokenType::ARROW => Some(Box::from(LambdaParselet {})),
+            TokenType::LBRACKET => Some(Box::from(IndexParselet {})),
+            TokenType::RANGE => Some(Box::from(RangeParselet {})),
             _ => Some(Box::from(NullParset {})),
         }
     }
@@ -184,6 +294,7 @@ impl CalcParser<'_> {
             TokenType::GREATEREQ => Some(Box::from(OperatorPrefixParselet {})),
             TokenType::LBRACKET => Some(Box::from(VecParselet {})),
             TokenType::QUOTE => Some(Box::from(QuoteParselet {})),
+            TokenType::IF => Some(Box::from(IfParselet {})),
             _ => Some(Box::from(NullParselet {})),
         }
     }
@@ -191,11 +302,12 @@ impl CalcParser<'_> {
 
 #[cfg(test)]
 mod test {
-    use crate::lexing::lexer::lex;
+    use crate::lexing::lexer::{lex, lex_with_positions};
+    use crate::lexing::token::Token;
     use crate::parsing::ast::Parameters::{
         DivideOperation, MultiplicationOperation, PlusOperation,
     };
-    use crate::parsing::ast::{Ast, Parameters};
+    use crate::parsing::ast::{Ast, Parameters, Position};
     use crate::parsing::parser::{init_calc_parser, CalcParser};
 
     #[test]
@@ -215,6 +327,7 @@ mod test {
             value: Parameters::Int(2),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
+            position: Position::default(),
         };
 
         let result = parser.parse();
@@ -229,6 +342,7 @@ mod test {
             value: Parameters::PlusOperation,
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -244,8 +358,10 @@ mod test {
                 value: Parameters::PlusOperation,
                 left: Box::from(Ast::new(Parameters::Int(1))),
                 right: Box::from(Ast::new(Parameters::Int(1))),
+                position: Position::default(),
             }),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -259,6 +375,7 @@ mod test {
             value: Parameters::MinusOperation,
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -272,6 +389,7 @@ mod test {
             value: Parameters::MultiplicationOperation,
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -287,8 +405,10 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::new(Ast::new(Parameters::Int(2))),
                 right: Box::new(Ast::new(Parameters::Int(2))),
+                position: Position::default(),
             }),
             right: Box::from(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -304,8 +424,10 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::from(Ast::new(Parameters::Int(2))),
                 right: Box::from(Ast::new(Parameters::Int(2))),
+                position: Position::default(),
             }),
             right: Box::from(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -319,6 +441,7 @@ mod test {
             value: Parameters::DivideOperation,
             left: Box::new(Ast::new(Parameters::Int(2))),
             right: Box::new(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -332,6 +455,7 @@ mod test {
             value: Parameters::OrOperation,
             left: Box::new(Ast::new(Parameters::Bool(false))),
             right: Box::new(Ast::new(Parameters::Bool(true))),
+            position: Position::default(),
         };
         assert_eq!(parser.parse(), expected);
     }
@@ -344,6 +468,7 @@ mod test {
             value: Parameters::Assign,
             left: Box::new(Ast::new(Parameters::Identifier("i".to_string()))),
             right: Box::new(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected);
@@ -360,7 +485,9 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::new(Ast::new(Parameters::Int(1))),
                 right: Box::new(Ast::new(Parameters::Int(1))),
+                position: Position::default(),
             }),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -380,8 +507,11 @@ mod test {
                     value: Parameters::DivideOperation,
                     left: Box::from(Ast::new(Parameters::Int(1))),
                     right: Box::from(Ast::new(Parameters::Int(1))),
+                    position: Position::default(),
                 }),
+                position: Position::default(),
             }),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -398,7 +528,9 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::new(Ast::new(Parameters::Int(1))),
                 right: Box::new(Ast::new(Parameters::Int(1))),
+                position: Position::default(),
             }),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -418,9 +550,12 @@ mod test {
                     value: MultiplicationOperation,
                     left: Box::from(Ast::new(Parameters::Int(1))),
                     right: Box::from(Ast::new(Parameters::Int(1))),
+                    position: Position::default(),
                 }),
                 right: Box::from(Ast::new(Parameters::Int(1))),
+                position: Position::default(),
             }),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -437,7 +572,9 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::from(Ast::new(Parameters::Int(2))),
                 right: Box::from(Ast::new(Parameters::Int(2))),
+                position: Position::default(),
             }),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -453,8 +590,10 @@ mod test {
                 value: Parameters::MultiplicationOperation,
                 left: Box::from(Ast::new(Parameters::Int(2))),
                 right: Box::from(Ast::new(Parameters::Int(2))),
+                position: Position::default(),
             }),
             right: Box::from(Ast::new(Parameters::Int(1))),
+            position: Position::default(),
         };
         let result = parser.parse();
         assert_eq!(result, expected)
@@ -468,6 +607,53 @@ mod test {
             value: Parameters::Vector(Box::new(vec![Ast::new(Parameters::Int(2)); 4])),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
+            position: Position::default(),
+        };
+        assert_eq!(parser.parse(), expected);
+    }
+
+    #[test]
+    pub fn test_if_parsing() {
+        let b = lex("if x < 0 then 1 else 2".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = Ast::If {
+            condition: Box::new(Ast::Node {
+                value: Parameters::LesserOperation,
+                left: Box::new(Ast::new(Parameters::Identifier("x".to_string()))),
+                right: Box::new(Ast::new(Parameters::Int(0))),
+                position: Position::default(),
+            }),
+            then: Box::new(Ast::new(Parameters::Int(1))),
+            otherwise: Box::new(Ast::new(Parameters::Int(2))),
+            position: Position::default(),
+        };
+        assert_eq!(parser.parse(), expected);
+    }
+
+    #[test]
+    pub fn test_ternary_parsing() {
+        let b = lex("x == 0 ? 1 : x < 0 ? 2 : 3".to_string());
+        let parser = &mut init_calc_parser(&b);
+        let expected = Ast::If {
+            condition: Box::new(Ast::Node {
+                value: Parameters::Equal,
+                left: Box::new(Ast::new(Parameters::Identifier("x".to_string()))),
+                right: Box::new(Ast::new(Parameters::Int(0))),
+                position: Position::default(),
+            }),
+            then: Box::new(Ast::new(Parameters::Int(1))),
+            otherwise: Box::new(Ast::If {
+                condition: Box::new(Ast::Node {
+                    value: Parameters::LesserOperation,
+                    left: Box::new(Ast::new(Parameters::Identifier("x".to_string()))),
+                    right: Box::new(Ast::new(Parameters::Int(0))),
+                    position: Position::default(),
+                }),
+                then: Box::new(Ast::new(Parameters::Int(2))),
+                otherwise: Box::new(Ast::new(Parameters::Int(3))),
+                position: Position::default(),
+            }),
+            position: Position::default(),
         };
         assert_eq!(parser.parse(), expected);
     }
@@ -480,7 +666,87 @@ mod test {
             value: Parameters::Str("test 1 2 1 2".to_string()),
             left: Box::new(Ast::Nil),
             right: Box::new(Ast::Nil),
+            position: Position::default(),
         };
         assert_eq!(parser.parse(), expected);
     }
+
+    #[test]
+    pub fn test_unexpected_token_position() {
+        let (b, positions): (Vec<Token>, Vec<usize>) = lex_with_positions("(1 + 2))".to_string())
+            .into_iter()
+            .unzip();
+        let parser = &mut init_calc_parser(&b).with_positions(positions);
+        match parser.parse() {
+            Ast::Node {
+                value: Parameters::Error(e),
+                ..
+            } => {
+                assert_eq!(e.message, "unexpected ')'");
+                assert_eq!(e.position, Some(7))
+            }
+            a => panic!("expected a syntax error, got {a}"),
+        }
+    }
+
+    #[test]
+    pub fn test_unterminated_string() {
+        let (b, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions("\"abc".to_string()).into_iter().unzip();
+        let parser = &mut init_calc_parser(&b).with_positions(positions);
+        match parser.parse() {
+            Ast::Node {
+                value: Parameters::Error(e),
+                ..
+            } => {
+                assert_eq!(e.message, "unterminated string");
+                assert_eq!(e.position, Some(1))
+            }
+            a => panic!("expected a syntax error, got {a}"),
+        }
+    }
+
+    #[test]
+    pub fn test_missing_parenthesis() {
+        let (b, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions("f(1, 2".to_string()).into_iter().unzip();
+        let parser = &mut init_calc_parser(&b).with_positions(positions);
+        match parser.parse() {
+            Ast::Node {
+                value: Parameters::Error(e),
+                ..
+            } => {
+                assert_eq!(e.message, "expected ')' but found end of input");
+                assert_eq!(e.position, Some(6))
+            }
+            a => panic!("expected a syntax error, got {a}"),
+        }
+    }
+
+    #[test]
+    pub fn test_ast_positions() {
+        let (b, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions("1 + f(x, 2 * y)".to_string())
+                .into_iter()
+                .unzip();
+        let parser = &mut init_calc_parser(&b).with_positions(positions);
+        match parser.parse() {
+            Ast::Node {
+                position, right: r, ..
+            } => {
+                assert_eq!(position.column, Some(2));
+                assert_eq!(position.span, Some((0, None)));
+                match *r {
+                    Ast::Call { position, lst, .. } => {
+                        assert_eq!(position.column, Some(4));
+                        assert_eq!(position.span, Some((4, None)));
+                        assert_eq!(lst[1].position().column, Some(11));
+                        assert_eq!(lst[1].position().span, Some((9, Some(14))));
+                    }
+                    a => panic!("expected a call, got {a}"),
+                }
+            }
+            a => panic!("expected a node, got {a}"),
+        }
+    }
 }
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::f64::consts::{E, PI};
+use std::io::Write;
+
+use num_complex::Complex64;
+
+use crate::interpreting::error::{unknown_variable, CalcError};
+use crate::interpreting::interpreter::interpret;
+use crate::lexing::lexer::lex_with_positions;
+use crate::lexing::token::Token;
+use crate::parsing::ast::{ast_to_source, value_to_source, Ast, Parameters, Position};
+use crate::parsing::parser::init_calc_parser;
+
+pub fn init_ram() -> HashMap<String, Parameters> {
+    let mut ram = HashMap::new();
+    ram.insert("pi".to_string(), Parameters::Float(PI));
+    ram.insert("e".to_string(), Parameters::Float(E));
+    ram.insert("i".to_string(), Parameters::Complex(Complex64::i()));
+    ram
+}
+
+pub fn format_error(e: &CalcError, line: &str) -> String {
+    let e = e.clone().with_source(line);
+    match e.position {
+        None => e.to_string(),
+        Some(p) => format!("{line}\n{}^\n{e}", " ".repeat(p)),
+    }
+}
+
+pub fn unknown_to_error(result: Parameters, ram: &HashMap<String, Parameters>) -> Parameters {
+    match result {
+        Parameters::Identifier(s) if !s.starts_with("@") && !ram.contains_key(&s) => {
+            unknown_variable(&s)
+        }
+        _ => result,
+    }
+}
+
+pub fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn is_assignment(ast: &Ast) -> bool {
+    matches!(
+        ast,
+        Ast::Node {
+            value: Parameters::Assign,
+            ..
+        }
+    )
+}
+
+pub fn remember_result(
+    ast: &Ast,
+    result: &Parameters,
+    ram: &mut HashMap<String, Parameters>,
+    index: usize,
+) -> bool {
+    let value = match result {
+        Parameters::Null | Parameters::Error(_) => return false,
+        _ if is_assignment(ast) => return false,
+        Parameters::Identifier(s) if s.starts_with("@") => return false,
+        Parameters::Identifier(s) => match ram.get(s) {
+            Some(v) => v.clone(),
+            None => return false,
+        },
+        v => v.clone(),
+    };
+    ram.insert(format!("${index}"), value.clone());
+    ram.insert("ans".to_string(), value);
+    true
+}
+
+#[derive(Debug)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+pub fn run_script(
+    source: &str,
+    ram: &mut HashMap<String, Parameters>,
+    functions: &mut HashMap<String, (Vec<Ast>, Ast)>,
+    out: &mut impl Write,
+) -> Result<(), ScriptError> {
+    for (n, raw) in source.lines().enumerate() {
+        let line = strip_comment(raw).trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (lexed, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions(line.to_string()).into_iter().unzip();
+        let parsed = init_calc_parser(&lexed).with_positions(positions).parse();
+        let result = unknown_to_error(interpret(&parsed, ram, functions), ram);
+        match result {
+            Parameters::Error(e) => {
+                return Err(ScriptError {
+                    line: n + 1,
+                    message: format_error(&e, line),
+                })
+            }
+            Parameters::Null => (),
+            _ if is_assignment(&parsed) => (),
+            _ => {
+                let _ = writeln!(out, "{}", result.pretty_print(Some(ram), Some(functions)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_result_reference(name: &str) -> bool {
+    name == "ans" || name.starts_with('$')
+}
+
+pub fn save_session(
+    ram: &HashMap<String, Parameters>,
+    functions: &HashMap<String, (Vec<Ast>, Ast)>,
+) -> String {
+    let defaults = init_ram();
+    let mut lines = vec!["# mini-calc session".to_string()];
+
+    let mut names: Vec<&String> = ram
+        .keys()
+        .filter(|k| defaults.get(*k) != ram.get(*k) && !is_result_reference(k))
+        .collect();
+    names.sort();
+    for name in names {
+        if let Some(v) = value_to_source(&ram[name]) {
+            lines.push(format!("{name} = {v}"));
+        }
+    }
+
+    let mut names: Vec<&String> = functions.keys().collect();
+    names.sort();
+    for name in names {
+        let (args, body) = &functions[name];
+        let call = Ast::Call {
+            name: name.to_string(),
+            lst: args.clone(),
+            position: Position::default(),
+        };
+        if let (Some(c), Some(b)) = (ast_to_source(&call), ast_to_source(body)) {
+            lines.push(format!("{c} = {b}"));
+        }
+    }
+
+    lines.push("".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::interpreting::interpreter::interpret;
+    use crate::lexing::lexer::lex_with_positions;
+    use crate::lexing::token::Token;
+    use crate::parsing::ast::Parameters;
+    use crate::parsing::parser::init_calc_parser;
+
+    use super::{init_ram, remember_result, run_script, save_session};
+
+    fn run(source: &str) -> (String, Option<usize>) {
+        let mut ram = init_ram();
+        let mut functions = HashMap::new();
+        let mut out = Vec::new();
+        let result = run_script(source, &mut ram, &mut functions, &mut out);
+        (
+            String::from_utf8(out).unwrap(),
+            result.err().map(|e| e.line),
+        )
+    }
+
+    #[test]
+    pub fn test_script() {
+        let source = "# rectangle area\nw = 3\nh = 4 # metres\n\nf(x,y) = x * y\nf(w,h)\nw + h\n";
+        assert_eq!(run(source), ("12\n7\n".to_string(), None));
+    }
+
+    #[test]
+    pub fn test_save_and_load_session() {
+        let source = "x = 1/3\ny = [[1,2],[3,4.5]]\nz = 2-3*i\ns = \"a # b\"\nf(x,y) = if x > 0 then -x^y else y\nk = 2\ng = (x,y) -> k*x + y\np = (a+1)^2/(2*b)\n";
+        let mut ram = init_ram();
+        let mut functions = HashMap::new();
+        run_script(source, &mut ram, &mut functions, &mut Vec::new()).unwrap();
+
+        let saved = save_session(&ram, &functions);
+        let mut ram2 = init_ram();
+        let mut functions2 = HashMap::new();
+        run_script(&saved, &mut ram2, &mut functions2, &mut Vec::new()).unwrap();
+
+        assert_eq!(ram, ram2);
+        assert_eq!(saved, save_session(&ram2, &functions2));
+        let mut out = Vec::new();
+        run_script("f(2,3)\nf(-2,3)", &mut ram2, &mut functions2, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "-8\n3\n");
+    }
+
+    #[test]
+    pub fn test_save_and_load_floats() {
+        let source = "x = sqrt(2)\ny = -exp(-740)\nz = [sqrt(3), 2*sqrt(-2)]\nw = exp(700)\nf = t -> 1.5*t\n";
+        let mut ram = init_ram();
+        let mut functions = HashMap::new();
+        run_script(source, &mut ram, &mut functions, &mut Vec::new()).unwrap();
+
+        let saved = save_session(&ram, &functions);
+        let mut ram2 = init_ram();
+        let mut functions2 = HashMap::new();
+        run_script(&saved, &mut ram2, &mut functions2, &mut Vec::new()).unwrap();
+
+        assert_eq!(ram2.get("x"), Some(&Parameters::Float(2f64.sqrt())));
+        assert_eq!(ram, ram2);
+    }
+
+    #[test]
+    pub fn test_remember_result() {
+        let mut ram = init_ram();
+        let mut functions = HashMap::new();
+        let mut index = 1;
+        for line in ["x = 2", "x", "ans * 3", "f(y) = y", "$1 + $2"] {
+            let (lexed, positions): (Vec<Token>, Vec<usize>) =
+                lex_with_positions(line.to_string()).into_iter().unzip();
+            let parsed = init_calc_parser(&lexed).with_positions(positions).parse();
+            let result = interpret(&parsed, &mut ram, &mut functions);
+            if remember_result(&parsed, &result, &mut ram, index) {
+                index += 1;
+            }
+        }
+        assert_eq!(index, 4);
+        assert_eq!(ram.get("$2"), Some(&Parameters::Int(6)));
+        assert_eq!(ram.get("ans"), Some(&Parameters::Int(8)));
+    }
+
+    #[test]
+    pub fn test_save_skips_results() {
+        let mut ram = init_ram();
+        let mut functions = HashMap::new();
+        run_script("x = 2", &mut ram, &mut functions, &mut Vec::new()).unwrap();
+        let (lexed, positions): (Vec<Token>, Vec<usize>) =
+            lex_with_positions("x * 3".to_string()).into_iter().unzip();
+        let parsed = init_calc_parser(&lexed).with_positions(positions).parse();
+        let result = interpret(&parsed, &mut ram, &mut functions);
+        assert!(remember_result(&parsed, &result, &mut ram, 1));
+
+        let saved = save_session(&ram, &functions);
+        assert_eq!(saved, "# mini-calc session\nx = 2\n");
+    }
+
+    #[test]
+    pub fn test_script_error_line() {
+        let source = "a = 1\na + 1\nsqrt(b)\na + 2\n";
+        assert_eq!(run(source), ("2\n".to_string(), Some(3)));
+    }
+
+    #[test]
+    pub fn test_script_error_source() {
+        let cases = [
+            ("v = [1,2]\nv[-5]", " in v[-5]"),
+            ("reduce((a,b)->a+b, [])", " in reduce((a,b)->a+b, [])"),
+            ("1..5 step 0", " in 1..5 step 0"),
+            ("2 * (1/0)", " in 1/0"),
+            ("f(x) = 100/x\nf(0)", " in 100 / x"),
+        ];
+        for (source, expression) in cases {
+            let mut ram = init_ram();
+            let mut functions = HashMap::new();
+            match run_script(source, &mut ram, &mut functions, &mut Vec::new()) {
+                Err(e) => assert!(e.message.ends_with(expression), "{}", e.message),
+                Ok(()) => panic!("{source} should fail"),
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use num_complex::Complex64;
+
+use crate::parsing::ast::Parameters;
+
+pub fn to_complex(p: &Parameters) -> Option<Complex64> {
+    match p {
+        Parameters::Int(i) => Some(Complex64::new(*i as f64, 0.0)),
+        Parameters::Float(f) => Some(Complex64::new(*f, 0.0)),
+        Parameters::Rational(s) => Some(Complex64::new(s.clone().approx(), 0.0)),
+        Parameters::Complex(c) => Some(*c),
+        _ => None,
+    }
+}
+
+pub fn complex_operands(p: &Parameters, p2: &Parameters) -> Option<(Complex64, Complex64)> {
+    match (p, p2) {
+        (Parameters::Complex(_), _) | (_, Parameters::Complex(_)) => {
+            Some((to_complex(p)?, to_complex(p2)?))
+        }
+        _ => None,
+    }
+}
+
+pub fn is_negative_real(p: &Parameters) -> bool {
+    match to_complex(p) {
+        Some(c) => c.im == 0.0 && c.re < 0.0 && !matches!(p, Parameters::Complex(_)),
+        None => false,
+    }
+}
+
+pub fn complex_result(c: Complex64) -> Parameters {
+    if c.im == 0.0 {
+        Parameters::Float(c.re)
+    } else {
+        Parameters::Complex(c)
+    }
+}
+
+pub fn format_part(f: f64) -> String {
+    let s = format!("{:.10}", f);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn format_complex(c: &Complex64) -> String {
+    let im = match format_part(c.im.abs()).as_str() {
+        "1" => "".to_string(),
+        s => s.to_string(),
+    };
+    if format_part(c.re) == "0" {
+        let sign = if c.im < 0.0 { "-" } else { "" };
+        format!("{sign}{im}i")
+    } else {
+        let sign = if c.im < 0.0 { "-" } else { "+" };
+        format!("{}{sign}{im}i", format_part(c.re))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex64;
+
+    use super::format_complex;
+
+    #[test]
+    pub fn test_format_complex() {
+        assert_eq!(format_complex(&Complex64::new(1.0, 2.0)), "1+2i");
+        assert_eq!(format_complex(&Complex64::new(0.5, -1.0)), "0.5-i");
+        assert_eq!(format_complex(&Complex64::new(0.0, -3.0)), "-3i");
+    }
+}
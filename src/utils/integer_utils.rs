@@ -1,9 +1,330 @@
-pub fn gcd(mut n: i64, mut m: i64) -> i64 {
+use num_bigint::BigInt;
+use num_integer::{ExtendedGcd, Integer};
+use num_traits::{One, Signed, ToPrimitive};
+
+const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+const TRIAL_DIVISION_BOUND: u64 = 1000;
+const MAX_POLLARD_STEPS: usize = 100_000;
+const MAX_POLLARD_CONSTANTS: u64 = 10;
+const POLLARD_BATCH: usize = 32;
+
+pub fn gcd(n: i64, m: i64) -> i64 {
+    let (mut n, mut m) = (n.unsigned_abs(), m.unsigned_abs());
     while m != 0 {
-        if m < n {
-            std::mem::swap(&mut m, &mut n);
+        (n, m) = (m, n % m);
+    }
+    n as i64
+}
+
+pub fn lcm(n: &BigInt, m: &BigInt) -> BigInt {
+    n.lcm(m)
+}
+
+pub fn binomial(n: i64, k: i64) -> BigInt {
+    if k < 0 || k > n {
+        return BigInt::from(0);
+    }
+    let k = k.min(n - k);
+    (1..=k).fold(BigInt::from(1), |acc, i| acc * (n - k + i) / i)
+}
+
+pub fn permutations(n: i64, k: i64) -> BigInt {
+    if k < 0 || k > n {
+        return BigInt::from(0);
+    }
+    (n - k + 1..=n).fold(BigInt::from(1), |acc, i| acc * i)
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+fn is_small_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in MILLER_RABIN_BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let (mut d, mut s) = (n - 1, 0);
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    MILLER_RABIN_BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+pub fn is_prime(n: &BigInt) -> bool {
+    if let Some(n) = n.to_u64() {
+        return is_small_prime(n);
+    }
+    if n.is_negative()
+        || MILLER_RABIN_BASES
+            .iter()
+            .any(|p| n.is_multiple_of(&BigInt::from(*p)))
+    {
+        return false;
+    }
+    let one = BigInt::one();
+    let predecessor: BigInt = n - 1;
+    let s = predecessor.trailing_zeros().unwrap_or(0);
+    let d = &predecessor >> s;
+    MILLER_RABIN_BASES.iter().all(|&a| {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x == one || x == predecessor {
+            return true;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == predecessor {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+pub fn next_prime(n: &BigInt) -> BigInt {
+    let mut candidate = n.max(&BigInt::one()) + 1;
+    while !is_prime(&candidate) {
+        candidate += 1;
+    }
+    candidate
+}
+
+fn pollard_rho(n: u64) -> u64 {
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+    for c in 1.. {
+        let f = |x: u64| (mul_mod(x, x, n) + c) % n;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y) as i64, n as i64) as u64;
+        }
+        if d != n {
+            return d;
         }
-        m %= n;
     }
     n
 }
+
+fn big_pollard_rho(n: &BigInt) -> Option<BigInt> {
+    let mut steps = 0;
+    for c in 1..=MAX_POLLARD_CONSTANTS {
+        let f = |x: &BigInt| (x * x + c) % n;
+        let (mut x, mut y, mut d) = (BigInt::from(2), BigInt::from(2), BigInt::one());
+        while d.is_one() {
+            if steps >= MAX_POLLARD_STEPS {
+                return None;
+            }
+            let mut product = BigInt::one();
+            for _ in 0..POLLARD_BATCH {
+                x = f(&x);
+                y = f(&f(&y));
+                product = product * (&x - &y).abs() % n;
+            }
+            steps += POLLARD_BATCH;
+            d = product.gcd(n);
+        }
+        if &d != n {
+            return Some(d);
+        }
+    }
+    None
+}
+
+fn prime_divisors(n: u64, divisors: &mut Vec<u64>) {
+    if n <= 1 {
+        return;
+    }
+    if is_small_prime(n) {
+        divisors.push(n);
+        return;
+    }
+    let d = pollard_rho(n);
+    prime_divisors(d, divisors);
+    prime_divisors(n / d, divisors);
+}
+
+fn big_prime_divisors(n: BigInt, divisors: &mut Vec<BigInt>) -> Option<()> {
+    if let Some(n) = n.to_u64() {
+        let mut small = vec![];
+        prime_divisors(n, &mut small);
+        divisors.extend(small.into_iter().map(BigInt::from));
+        return Some(());
+    }
+    if is_prime(&n) {
+        divisors.push(n);
+        return Some(());
+    }
+    let d = big_pollard_rho(&n)?;
+    let quotient = &n / &d;
+    big_prime_divisors(d, divisors)?;
+    big_prime_divisors(quotient, divisors)
+}
+
+pub fn factorize(n: &BigInt) -> Option<Vec<(BigInt, u32)>> {
+    let mut n = n.abs();
+    let mut divisors = vec![];
+    let mut p = BigInt::from(2);
+    while p <= BigInt::from(TRIAL_DIVISION_BOUND) && &p * &p <= n {
+        while n.is_multiple_of(&p) {
+            divisors.push(p.clone());
+            n /= &p;
+        }
+        p += 1;
+    }
+    big_prime_divisors(n, &mut divisors)?;
+    divisors.sort();
+    let mut factors: Vec<(BigInt, u32)> = vec![];
+    for d in divisors {
+        match factors.last_mut() {
+            Some((p, k)) if *p == d => *k += 1,
+            _ => factors.push((d, 1)),
+        }
+    }
+    Some(factors)
+}
+
+pub fn totient(n: &BigInt) -> Option<BigInt> {
+    Some(
+        factorize(n)?
+            .iter()
+            .fold(n.abs(), |acc, (p, _)| acc / p * (p - 1)),
+    )
+}
+
+pub fn mod_inverse(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+    let m = m.abs();
+    let ExtendedGcd { gcd, x, .. } = a.mod_floor(&m).extended_gcd(&m);
+    match gcd.is_one() {
+        true => Some(x.mod_floor(&m)),
+        false => None,
+    }
+}
+
+pub fn mod_pow(base: &BigInt, exponent: &BigInt, m: &BigInt) -> Option<BigInt> {
+    let base = match exponent.is_negative() {
+        true => mod_inverse(base, m)?,
+        false => base.clone(),
+    };
+    let m = m.abs();
+    Some(base.mod_floor(&m).modpow(&exponent.abs(), &m))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        binomial, factorize, gcd, is_prime, lcm, mod_inverse, mod_pow, next_prime, totient,
+    };
+    use num_bigint::BigInt;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-4, 6), 2);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(&BigInt::from(4), &BigInt::from(-6)), BigInt::from(12));
+        assert_eq!(binomial(52, 5), BigInt::from(2_598_960));
+    }
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    #[test]
+    fn test_primes() {
+        assert!(is_prime(&big(2)));
+        assert!(!is_prime(&big(1)));
+        assert!(!is_prime(&big(561)));
+        assert!(is_prime(&big(1_000_000_007)));
+        assert!(is_prime(&big(9_223_372_036_854_775_783)));
+        assert_eq!(next_prime(&big(13)), big(17));
+        assert_eq!(
+            next_prime(&big(i64::MAX)),
+            BigInt::from(9_223_372_036_854_775_837_u64)
+        );
+    }
+
+    #[test]
+    fn test_big_primes() {
+        let mersenne = (BigInt::from(1) << 89) - 1;
+        assert!(is_prime(&mersenne));
+        assert!(!is_prime(&(&mersenne + 2)));
+        assert!(!is_prime(&(BigInt::from(1) << 70)));
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(
+            factorize(&big(360)),
+            Some(vec![(big(2), 3), (big(3), 2), (big(5), 1)])
+        );
+        assert_eq!(factorize(&big(1)), Some(vec![]));
+        assert_eq!(
+            factorize(&big(999_999_000_001 * 7)),
+            Some(vec![(big(7), 1), (big(999_999_000_001), 1)])
+        );
+        assert_eq!(
+            factorize(&big(1_000_000_007 * 998_244_353)),
+            Some(vec![(big(998_244_353), 1), (big(1_000_000_007), 1)])
+        );
+        assert_eq!(totient(&big(36)), Some(big(12)));
+    }
+
+    #[test]
+    fn test_factorize_big() {
+        assert_eq!(
+            factorize(&(BigInt::from(1) << 70)),
+            Some(vec![(big(2), 70)])
+        );
+        let n = BigInt::from(1_000_000_007) * 998_244_353 * 1_000_000_009;
+        assert_eq!(
+            factorize(&n),
+            Some(vec![
+                (big(998_244_353), 1),
+                (big(1_000_000_007), 1),
+                (big(1_000_000_009), 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(&big(3), &big(11)), Some(big(4)));
+        assert_eq!(mod_inverse(&big(2), &big(4)), None);
+        assert_eq!(mod_pow(&big(4), &big(13), &big(497)), Some(big(445)));
+        assert_eq!(mod_pow(&big(3), &big(-1), &big(11)), Some(big(4)));
+        assert_eq!(mod_pow(&big(-2), &big(3), &big(5)), Some(big(2)));
+    }
+}
@@ -66,6 +66,7 @@ pub fn lup_decompose(
     let mut max_a;
     let mut ptr: Vec<Parameters>;
     let mut i_max: usize;
+    let mut singular = false;
 
     for i in 0..(n + 1) {
         (&mut p)[i] = Parameters::Int(i as i64);
@@ -86,14 +87,18 @@ pub fn lup_decompose(
             }
         }
 
-        match max_a {
-            Parameters::Int(0) => return 0,
-            Parameters::Float(f) => {
-                if f.abs() <= 1e-10 {
-                    return 0;
-                }
+        let negligible = match max_a {
+            Parameters::Int(0) => true,
+            Parameters::Float(f) => f.abs() <= 1e-10,
+            _ => false,
+        };
+
+        if negligible {
+            singular = true;
+            for row in a[(i + 1)..n].iter_mut() {
+                row[i] = Parameters::Int(0);
             }
-            _ => (),
+            continue;
         }
 
         if i_max != i {
@@ -119,6 +124,9 @@ pub fn lup_decompose(
             }
         }
     }
+    if singular {
+        return 0;
+    }
     return 1;
 }
 
@@ -221,6 +229,277 @@ pub fn lup_invert(
     }
 }
 
+pub fn lup_solve(
+    a: &[Vec<Parameters>],
+    p: &[Parameters],
+    b: &[Parameters],
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Vec<Parameters> {
+    let n = a.len();
+    let mut x: Vec<Parameters> = Vec::with_capacity(n);
+    for i in 0..n {
+        let row = match p[i] {
+            Parameters::Int(r) => r as usize,
+            _ => i,
+        };
+        let mut y = b[row].clone();
+        for k in 0..i {
+            y = minus(y, mult(a[i][k].clone(), x[k].clone(), ram), ram);
+        }
+        x.push(y);
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            x[i] = minus(x[i].clone(), mult(a[i][k].clone(), x[k].clone(), ram), ram);
+        }
+        x[i] = divide(x[i].clone(), a[i][i].clone(), ram);
+    }
+    x
+}
+
+pub fn normal_equations(
+    a: &[Vec<Parameters>],
+    b: &[Vec<Parameters>],
+    ram: Option<&HashMap<String, Parameters>>,
+) -> (Vec<Vec<Parameters>>, Vec<Vec<Parameters>>) {
+    let dot = |u: usize, v: &dyn Fn(usize) -> Parameters| {
+        (0..a.len()).fold(Parameters::Int(0), |acc, k| {
+            add(acc, mult(a[k][u].clone(), v(k), ram), ram)
+        })
+    };
+    let n = a.first().map_or(0, |r| r.len());
+    let ata = (0..n)
+        .map(|i| (0..n).map(|j| dot(i, &|k| a[k][j].clone())).collect())
+        .collect();
+    let atb = b
+        .iter()
+        .map(|column| (0..n).map(|i| dot(i, &|k| column[k].clone())).collect())
+        .collect();
+    (ata, atb)
+}
+
+pub fn trace(a: &[Vec<Parameters>], ram: Option<&HashMap<String, Parameters>>) -> Parameters {
+    a.iter()
+        .enumerate()
+        .fold(Parameters::Int(0), |acc, (i, row)| {
+            add(acc, row[i].clone(), ram)
+        })
+}
+
+fn magnitude(x: &Parameters) -> f64 {
+    x.clone().abs(None).to_f64().unwrap_or(0.0)
+}
+
+fn is_negligible(x: &Parameters, scale: f64) -> bool {
+    match x {
+        Parameters::Int(_) | Parameters::Rational(_) => magnitude(x) == 0.0,
+        _ => magnitude(x) <= 1e-9 * scale,
+    }
+}
+
+pub fn row_echelon(
+    a: &mut [Vec<Parameters>],
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Vec<usize> {
+    let (m, n) = (a.len(), a.first().map_or(0, |r| r.len()));
+    let scale = a.iter().flatten().map(magnitude).fold(1.0, f64::max);
+    let mut pivots = Vec::new();
+    for col in 0..n {
+        let row = pivots.len();
+        if row == m {
+            break;
+        }
+        let best = (row..m)
+            .max_by(|i, j| magnitude(&a[*i][col]).total_cmp(&magnitude(&a[*j][col])))
+            .unwrap_or(row);
+        if is_negligible(&a[best][col], scale) {
+            continue;
+        }
+        a.swap(row, best);
+        let pivot = a[row][col].clone();
+        for x in a[row].iter_mut().skip(col) {
+            *x = divide(x.clone(), pivot.clone(), ram);
+        }
+        let pivot_row = a[row].clone();
+        for (i, r) in a.iter_mut().enumerate() {
+            let factor = r[col].clone();
+            if i == row || is_negligible(&factor, 0.0) {
+                continue;
+            }
+            for (x, p) in r.iter_mut().zip(&pivot_row).skip(col) {
+                *x = minus(x.clone(), mult(factor.clone(), p.clone(), ram), ram);
+            }
+        }
+        pivots.push(col);
+    }
+    pivots
+}
+
+pub fn kernel(
+    a: &[Vec<Parameters>],
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Vec<Vec<Parameters>> {
+    let n = a.first().map_or(0, |r| r.len());
+    let mut reduced = a.to_vec();
+    let pivots = row_echelon(&mut reduced, ram);
+    (0..n)
+        .filter(|j| !pivots.contains(j))
+        .map(|free| {
+            let mut v = vec![Parameters::Int(0); n];
+            v[free] = Parameters::Int(1);
+            for (row, col) in pivots.iter().enumerate() {
+                v[*col] = minus(Parameters::Int(0), reduced[row][free].clone(), ram);
+            }
+            v
+        })
+        .collect()
+}
+
+pub fn characteristic_polynomial(
+    a: &[Vec<Parameters>],
+    ram: Option<&HashMap<String, Parameters>>,
+) -> Vec<Parameters> {
+    let n = a.len();
+    let mut coefficients = vec![Parameters::Int(0); n + 1];
+    coefficients[n] = Parameters::Int(1);
+    let mut m = vec![vec![Parameters::Int(0); n]; n];
+    for k in 1..=n {
+        m = mult_matrix(a.to_vec(), m, ram);
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = add(row[i].clone(), coefficients[n - k + 1].clone(), ram);
+        }
+        let t = trace(&mult_matrix(a.to_vec(), m.clone(), ram), ram);
+        coefficients[n - k] = divide(
+            minus(Parameters::Int(0), t, ram),
+            Parameters::Int(k as i64),
+            ram,
+        );
+    }
+    coefficients
+}
+
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+pub fn householder_qr(a: &[Vec<f64>]) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let (m, n) = (a.len(), a.first().map_or(0, |r| r.len()));
+    let mut r = a.to_vec();
+    let mut q = identity(m);
+    for k in 0..n.min(m.saturating_sub(1)) {
+        let norm = r[k..].iter().map(|row| row[k] * row[k]).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        let alpha = if r[k][k] > 0.0 { -norm } else { norm };
+        let mut v: Vec<f64> = r.iter().map(|row| row[k]).collect();
+        v[..k].iter_mut().for_each(|x| *x = 0.0);
+        v[k] -= alpha;
+        let v2: f64 = v.iter().map(|x| x * x).sum();
+        if v2 == 0.0 {
+            continue;
+        }
+        for j in 0..n {
+            let s = 2.0 * r.iter().zip(&v).map(|(row, x)| row[j] * x).sum::<f64>() / v2;
+            r.iter_mut().zip(&v).for_each(|(row, x)| row[j] -= s * x);
+        }
+        for row in q.iter_mut() {
+            let s = 2.0 * row.iter().zip(&v).map(|(y, x)| y * x).sum::<f64>() / v2;
+            row.iter_mut().zip(&v).for_each(|(y, x)| *y -= s * x);
+        }
+        r[k + 1..].iter_mut().for_each(|row| row[k] = 0.0);
+    }
+    for k in 0..n.min(m) {
+        if r[k][k] < 0.0 {
+            r[k].iter_mut().for_each(|x| *x = -*x);
+            q.iter_mut().for_each(|row| row[k] = -row[k]);
+        }
+    }
+    (q, r)
+}
+
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+fn rotate(columns: &mut [Vec<f64>], p: usize, q: usize, c: f64, s: f64) {
+    let (left, right) = columns.split_at_mut(q);
+    for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+        let (a, b) = (*x, *y);
+        *x = c * a - s * b;
+        *y = s * a + c * b;
+    }
+}
+
+fn orthogonal_complement(columns: &[Vec<f64>], m: usize) -> Vec<f64> {
+    let candidates = identity(m).into_iter().map(|mut e| {
+        for c in columns {
+            let projection = c.iter().zip(&e).map(|(x, y)| x * y).sum::<f64>();
+            e.iter_mut().zip(c).for_each(|(y, x)| *y -= projection * x);
+        }
+        e
+    });
+    let best = candidates
+        .map(|e| (e.iter().map(|x| x * x).sum::<f64>().sqrt(), e))
+        .reduce(|best, e| if e.0 > best.0 { e } else { best })
+        .unwrap_or((1.0, vec![0.0; m]));
+    best.1.iter().map(|x| x / best.0).collect()
+}
+
+pub fn jacobi_svd(a: &[Vec<f64>]) -> (Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>) {
+    let (m, n) = (a.len(), a.first().map_or(0, |r| r.len()));
+    if m < n {
+        let (u, s, v) = jacobi_svd(&transpose(a.to_vec()));
+        return (v, s, u);
+    }
+    let dot = |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(a, b)| a * b).sum::<f64>();
+    let mut u = transpose(a.to_vec());
+    let mut v = identity(n);
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha = dot(&u[p], &u[p]);
+                let beta = dot(&u[q], &u[q]);
+                let gamma = dot(&u[p], &u[q]);
+                if gamma.abs() <= 1e-15 * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                rotate(&mut u, p, q, c, c * t);
+                rotate(&mut v, p, q, c, c * t);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+    let sigma: Vec<f64> = u.iter().map(|col| dot(col, col).sqrt()).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|i, j| sigma[*j].total_cmp(&sigma[*i]));
+    let largest = sigma.iter().cloned().fold(0.0, f64::max);
+    let mut u_columns: Vec<Vec<f64>> = order
+        .iter()
+        .map(|j| sigma[*j])
+        .take_while(|s| *s > 1e-15 * largest)
+        .zip(&order)
+        .map(|(s, j)| u[*j].iter().map(|x| x / s).collect())
+        .collect();
+    while u_columns.len() < n {
+        let column = orthogonal_complement(&u_columns, m);
+        u_columns.push(column);
+    }
+    let v_columns = order.iter().map(|j| v[*j].clone()).collect();
+    (
+        transpose(u_columns),
+        order.iter().map(|j| sigma[*j]).collect(),
+        transpose(v_columns),
+    )
+}
+
 #[cfg(test)]
 mod test {
 